@@ -24,14 +24,85 @@ fn mix_colors(a: vec4<f32>, b: vec4<f32>) -> vec4<f32> {
     return vec4(a.rgb * (1.0 - b.a) + b.rgb * b.a, a.a * (1.0 - b.a) + b.a);
 }
 
+const MONSTER_STATE_FACING_LEFT_BIT: u32 = 1u;
+const MONSTER_STATE_HURT_BIT: u32 = 16u;
+const MONSTER_STATE_DYING_BIT: u32 = 32u;
+
+// Index of MonsterState in bits 16..19, must match MonsterState::get_index
+const MONSTER_AI_ALERT: u32 = 2u;
+const MONSTER_AI_CHASE: u32 = 3u;
+const MONSTER_AI_STUNNED: u32 = 5u;
+
+// Must match SideEffect::to_index, 0 means the thief carries nothing
+fn stolen_effect_color(effect: u32) -> vec3<f32> {
+    switch effect {
+        case 1u: { return vec3(0.2, 1.0, 0.2); }
+        case 2u: { return vec3(0.2, 1.0, 1.0); }
+        case 3u: { return vec3(0.3, 0.5, 1.5); }
+        case 4u: { return vec3(1.0, 0.4, 0.2); }
+        case 5u: { return vec3(1.5, 1.4, 0.5); }
+        case 6u: { return vec3(1.8, 0.2, 0.2); }
+        case 7u: { return vec3(1.2, 0.7, 0.1); }
+        default: { return vec3(0.0); }
+    }
+}
+
+// Rows of frames in monster.png, archetypes without their own row reuse the last one with a tint
+const MONSTER_TEXTURE_ROWS: u32 = 1u;
+
+fn monster_tint(index: u32) -> vec3<f32> {
+    switch index {
+        case 1u: { return vec3(0.6, 1.4, 0.6); }
+        case 2u: { return vec3(1.4, 1.2, 0.5); }
+        case 3u: { return vec3(0.7, 0.9, 1.6); }
+        case 4u: { return vec3(1.3, 0.6, 1.3); }
+        case 5u: { return vec3(0.5, 0.5, 0.6); }
+        default: { return vec3(1.0); }
+    }
+}
+
 @fragment
 fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
     var uv = in.uv;
-    if ((state & 1u) == 0u) {
+    if ((state & MONSTER_STATE_FACING_LEFT_BIT) == 0u) {
         uv.x = 1.0 - uv.x;
     }
-    let color = textureSample(texture, texture_sampler, vec2((uv.x + f32(animation_tick / 5u % 8u) + 2.0) / 10.0, (uv.y + f32(monster)) / 1.0));
-    let overlay = textureSample(texture, texture_sampler, vec2((uv.x + f32(state / 2u)) / 10.0, (uv.y + f32(monster)) / 1.0));
+    let row = f32(min(monster, MONSTER_TEXTURE_ROWS - 1u));
+    let rows = f32(MONSTER_TEXTURE_ROWS);
+    let color = textureSample(texture, texture_sampler, vec2((uv.x + f32(animation_tick / 5u % 8u) + 2.0) / 10.0, (uv.y + row) / rows));
+    let overlay = textureSample(texture, texture_sampler, vec2((uv.x + f32((state >> 1u) & 7u)) / 10.0, (uv.y + row) / rows));
+
+    var output_color = mix_colors(color, overlay);
+
+    if (monster >= MONSTER_TEXTURE_ROWS) {
+        output_color = vec4(output_color.rgb * monster_tint(monster), output_color.a);
+    }
+
+    let ai_state = (state >> 16u) & 7u;
+    if (ai_state == MONSTER_AI_ALERT && (animation_tick / 8u) % 2u == 0u) {
+        output_color = vec4(output_color.rgb + vec3(1.0, 1.0, 0.0), output_color.a);
+    }
+    if (ai_state == MONSTER_AI_CHASE) {
+        output_color = vec4(output_color.rgb * vec3(1.3, 0.8, 0.8), output_color.a);
+    }
+    if (ai_state == MONSTER_AI_STUNNED) {
+        output_color = vec4(output_color.rgb * 0.5, output_color.a);
+    }
+
+    let stolen_effect = (state >> 20u) & 7u;
+    if (stolen_effect != 0u) {
+        let pulse = 0.5 + 0.5 * sin(f32(animation_tick) * 0.15);
+        output_color = vec4(output_color.rgb + stolen_effect_color(stolen_effect) * pulse * output_color.a, output_color.a);
+    }
+
+    if ((state & MONSTER_STATE_HURT_BIT) != 0u && (animation_tick / 4u) % 2u == 0u) {
+        output_color = vec4(output_color.rgb + vec3(2.0, 0.0, 0.0), output_color.a);
+    }
+
+    if ((state & MONSTER_STATE_DYING_BIT) != 0u) {
+        let progress = f32((state >> 8u) & 255u) / 255.0;
+        output_color = vec4(output_color.rgb + vec3(4.0, 1.0, 1.0) * progress, output_color.a * (1.0 - progress));
+    }
 
-    return mix_colors(color, overlay);
+    return output_color;
 }
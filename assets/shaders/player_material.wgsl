@@ -3,11 +3,14 @@
 
 struct ColorMaterial {
     color: vec4<f32>,
-    sides: array<vec4<u32>, 4>,
+    sides: array<vec4<u32>, 6>,
     // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
     flags: u32,
+    number_of_sides: u32,
 };
 
+const PI: f32 = 3.14159265;
+
 const COLOR_MATERIAL_FLAGS_TEXTURE_BIT: u32 = 1u;
 const COLOR_MATERIAL_FLAGS_EMISSIVE_BIT: u32 = 2u;
 const COLOR_MATERIAL_FLAGS_OVERLAY_BIT: u32 = 4u;
@@ -112,9 +115,75 @@ fn mix_colors(a: vec4<f32>, b: vec4<f32>) -> vec4<f32> {
     return vec4(a.rgb * (1.0 - b.a) + b.rgb * b.a, a.a * (1.0 - b.a) + b.a);
 }
 
+fn sample_effect_side(effect: u32, uv: vec2<f32>) -> vec4<f32> {
+    var color = textureSample(player_effect_texture, player_effect_texture_sampler, vec2(max(uv.x, 0.5) / 4.0, (uv.y + f32(effect)) / 8.0));
+    let emissive = textureSample(player_effect_texture, player_effect_texture_sampler, vec2((uv.x - 0.5) / 4.0, (uv.y + f32(effect)) / 8.0));
+    if emissive.a > 0.0 {
+        color = vec4(color.rgb + emissive.rgb * 25.0, color.a);
+    }
+    return color;
+}
+
+fn sample_effect_addon(effect: u32, uv: vec2<f32>) -> vec4<f32> {
+    var color = textureSample(player_effect_texture, player_effect_texture_sampler, vec2((min(uv.x, 0.4) + 1.0) / 4.0, (uv.y + f32(effect)) / 8.0));
+    let emissive = textureSample(player_effect_texture, player_effect_texture_sampler, vec2((uv.x + 1.5) / 4.0, (uv.y + f32(effect)) / 8.0));
+    if emissive.a > 0.0 {
+        color = vec4(color.rgb + emissive.rgb * 25.0, color.a);
+    }
+    if any(abs(uv - 0.5) > vec2(0.5)) {
+        return vec4(0.0);
+    } else {
+        return color;
+    }
+}
+
+// Regular polygon with distance 0.5 from the center to every side, side 0 looks down.
+// `p` is the position relative to the center with y looking up.
+fn polygon_color(p: vec2<f32>) -> vec4<f32> {
+    let n = f32(material.number_of_sides);
+    let sector = 2.0 * PI / n;
+
+    // Side angles start from -PI/2 and go counter-clockwise
+    let angle = atan2(p.y, p.x) + PI * 0.5;
+    let side_index = i32(floor((angle + sector * 0.5) / sector + n)) % i32(material.number_of_sides);
+    let side_angle = -PI * 0.5 + sector * f32(side_index);
+
+    let normal = vec2(cos(side_angle), sin(side_angle));
+    let tangent = vec2(-normal.y, normal.x);
+    let side_length = tan(PI / n);
+
+    // Same side-local coordinates as the square uses: x goes from 0.5 in the center to 1.0 on the side
+    let side_uv = vec2(0.5 + dot(p, normal), 0.5 + dot(p, tangent) / side_length);
+    let effect = material.sides[side_index].x;
+
+    // Sample everything before branching, texture sampling needs uniform control flow
+    let texture_color = textureSample(texture, texture_sampler, vec2(p.x + 0.5, 0.5 - p.y));
+    let side_color = sample_effect_side(effect, side_uv);
+    let addon_color = sample_effect_addon(effect, vec2(side_uv.x - 1.0, side_uv.y));
+
+    if side_uv.x <= 1.0 {
+        var color = material.color;
+        if ((material.flags & COLOR_MATERIAL_FLAGS_TEXTURE_BIT) != 0u) {
+            color = color * texture_color;
+        }
+        return mix_colors(color, side_color);
+    } else {
+        return addon_color;
+    }
+}
+
 @fragment
 fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
     var output_color: vec4<f32> = vec4(0.0);
+
+    if (material.number_of_sides != 4u) {
+        output_color = polygon_color(vec2(in.uv.x * 2.0 - 1.0, 1.0 - in.uv.y * 2.0));
+        if (output_color.a < 0.01) {
+            output_color = vec4(output_color.rgb, 0.0);
+        }
+        return output_color;
+    }
+
     var uv = (in.uv * 2.0 - 0.5);
     uv = (uv - 0.5) * 0.999 + 0.5;
 
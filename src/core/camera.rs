@@ -1,48 +1,81 @@
 use bevy::prelude::*;
+use bevy_rapier2d::plugin::RapierConfiguration;
+use std::f32::consts::PI;
 
+use super::direction::SceneDirection;
 use super::objects::player::Player;
 use super::scene_builder::scene_boundaries::SceneBoundaries;
 
 pub struct CameraPlugin;
 
+const PLAYERS_MARGIN: f32 = 2.0;
+
 /// Controls camera zoom and movement during the game.
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_system(follow_players);
+        app.add_system(rotate_to_gravity);
     }
 }
 
 fn follow_players(
-    mut players: Query<&GlobalTransform, With<Player>>,
+    players: Query<&GlobalTransform, With<Player>>,
     mut cameras: Query<(&mut Transform, &Camera), (With<Camera2d>, Without<Player>)>,
     boundaries: Res<SceneBoundaries>,
 ) {
-    for player in players.iter_mut() {
-        for (mut transform, camera) in cameras.iter_mut() {
-            if let Some(view_range) = boundaries.view_range {
-                let view_rect = get_view_rect(&camera, &transform);
-                let view_size = (view_rect.max - view_rect.min) * 0.5;
-                let zoom = if view_size.x > view_size.y {
-                    view_range / view_size.y
-                } else {
-                    view_range / view_size.x
-                };
-
-                transform.scale *= zoom.powf(0.05);
-            }
+    let Some(players_rect) = players
+        .iter()
+        .map(|player| Rect::from_center_size(player.translation().truncate(), Vec2::ZERO))
+        .reduce(|a, b| a.union(b))
+    else {
+        return;
+    };
+
+    // Keep some space around the players at the edges of the view
+    let players_half_size = players_rect.half_size() + PLAYERS_MARGIN;
+
+    for (mut transform, camera) in cameras.iter_mut() {
+        if let Some(view_range) = boundaries.view_range {
+            let view_rect = get_view_rect(&camera, &transform);
+            let view_size = (view_rect.max - view_rect.min) * 0.5;
+            let view_min_size = view_size.min_element();
 
-            let mut pos: Vec2 =
-                player.translation().truncate() * 0.05 + transform.translation.truncate() * 0.95;
+            // Range (along the smaller view side) needed to fit all the players
+            let required_range = (players_half_size * view_min_size / view_size).max_element();
 
-            // Clamp camera to boundaries
-            if let Some(boundaries) = boundaries.rect {
-                let view_rect = get_view_rect(&camera, &transform);
-                let view_size = (view_rect.max - view_rect.min) * 0.5;
-                pos = clamp_to_rect(pos, view_size, boundaries);
+            let mut target_range = required_range.max(view_range);
+            if let Some(max_view_range) = boundaries.max_view_range {
+                target_range = target_range.min(max_view_range.max(view_range));
             }
 
-            transform.translation = pos.extend(transform.translation.z);
+            let zoom = target_range / view_min_size;
+
+            transform.scale *= zoom.powf(0.05);
+        }
+
+        let mut pos: Vec2 = players_rect.center() * 0.05 + transform.translation.truncate() * 0.95;
+
+        // Clamp camera to boundaries
+        if let Some(boundaries) = boundaries.rect {
+            let view_rect = get_view_rect(&camera, &transform);
+            let view_size = (view_rect.max - view_rect.min) * 0.5;
+            pos = clamp_to_rect(pos, view_size, boundaries);
         }
+
+        transform.translation = pos.extend(transform.translation.z);
+    }
+}
+
+/// Rotates cameras so that gravity always points to the bottom of the screen.
+fn rotate_to_gravity(
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+    config: Res<RapierConfiguration>,
+) {
+    let gravity_direction = SceneDirection::from_gravity_direction(&config);
+    let target = Quat::from_rotation_z(gravity_direction.get_index() as f32 * PI * 0.5);
+
+    for mut transform in cameras.iter_mut() {
+        transform.rotation = transform.rotation.slerp(target, 0.08);
     }
 }
 
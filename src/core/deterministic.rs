@@ -0,0 +1,193 @@
+use bevy::ecs::event::Event;
+use bevy::ecs::system::SystemParamItem;
+use bevy::prelude::*;
+use bevy_rapier2d::plugin::systems::sync_removals;
+use bevy_rapier2d::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Settings of the deterministic mode, insert it before `CorePlugin` to enable the mode.
+///
+/// Gameplay systems always run in `CoreSchedule::FixedUpdate`. Without the mode it runs once per
+/// frame with the frame delta and Rapier keeps its default variable timestep. The mode moves Rapier
+/// into the same schedule, which `FixedTime` steps by `timestep` as many times as the frame needs,
+/// so they see the same delta at any frame rate. It also stores the step in replays and seeds
+/// `GameRng`, so identical inputs produce identical transforms.
+#[derive(Resource, Clone, Debug)]
+pub struct DeterministicSettings {
+    pub timestep: f32,
+    pub substeps: usize,
+    pub seed: u64,
+}
+
+impl Default for DeterministicSettings {
+    fn default() -> Self {
+        DeterministicSettings {
+            timestep: 1.0 / 60.0,
+            substeps: 5,
+            seed: 0,
+        }
+    }
+}
+
+/// Random number generator for gameplay, seeded in the deterministic mode.
+#[derive(Resource)]
+pub struct GameRng(pub StdRng);
+
+impl GameRng {
+    pub fn new(settings: Option<&DeterministicSettings>) -> Self {
+        match settings {
+            Some(settings) => GameRng(StdRng::seed_from_u64(settings.seed)),
+            None => GameRng(StdRng::from_entropy()),
+        }
+    }
+}
+
+/// Makes every `App::update` exactly one fixed step, no matter how much real time has passed.
+///
+/// The headless harness and fast replays use it to run as fast as the machine allows.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct StepPerUpdate;
+
+pub fn step_per_update(mut time: ResMut<Time>, mut fixed_time: ResMut<FixedTime>) {
+    // Real time would put extra steps into the accumulator
+    if !time.is_paused() {
+        time.pause();
+    }
+
+    let period = fixed_time.period;
+    fixed_time.tick(period);
+}
+
+/// Runs `CoreSchedule::FixedUpdate` once per frame with the frame delta.
+pub fn step_per_frame(time: Res<Time>, mut fixed_time: ResMut<FixedTime>) {
+    // Zero period would make the fixed schedule run forever
+    if !time.delta().is_zero() {
+        fixed_time.period = time.delta();
+    }
+}
+
+pub fn get_timestep_mode(settings: &DeterministicSettings) -> TimestepMode {
+    TimestepMode::Fixed {
+        dt: settings.timestep,
+        substeps: settings.substeps,
+    }
+}
+
+/// Orders base sets of `CoreSchedule::FixedUpdate` the same way as in the main schedule.
+pub fn configure_fixed_schedule(app: &mut App) {
+    app.edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+        schedule.set_default_base_set(CoreSet::Update);
+        schedule.configure_sets(
+            (
+                CoreSet::First,
+                CoreSet::Update,
+                CoreSet::UpdateFlush,
+                CoreSet::PostUpdate,
+            )
+                .chain(),
+        );
+        schedule.add_system(apply_system_buffers.in_base_set(CoreSet::UpdateFlush));
+    });
+}
+
+/// Moves Rapier into `CoreSchedule::FixedUpdate`, after gameplay systems of the same step.
+///
+/// Add `RapierPhysicsPlugin` without its default system setup and call `configure_fixed_schedule`
+/// before calling this.
+pub fn add_fixed_physics_systems<PhysicsHooks>(app: &mut App)
+where
+    PhysicsHooks: 'static + BevyPhysicsHooks,
+    for<'w, 's> SystemParamItem<'w, 's, PhysicsHooks>: BevyPhysicsHooks,
+{
+    app.edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+        schedule.configure_sets(
+            (
+                CoreSet::UpdateFlush,
+                PhysicsSet::SyncBackend,
+                PhysicsSet::SyncBackendFlush,
+                PhysicsSet::StepSimulation,
+                PhysicsSet::Writeback,
+                CoreSet::PostUpdate,
+            )
+                .chain(),
+        );
+    });
+
+    app.add_systems(
+        RapierPhysicsPlugin::<PhysicsHooks>::get_systems(PhysicsSet::SyncBackend)
+            .in_base_set(PhysicsSet::SyncBackend)
+            .in_schedule(CoreSchedule::FixedUpdate),
+    );
+    app.add_systems(
+        RapierPhysicsPlugin::<PhysicsHooks>::get_systems(PhysicsSet::SyncBackendFlush)
+            .in_base_set(PhysicsSet::SyncBackendFlush)
+            .in_schedule(CoreSchedule::FixedUpdate),
+    );
+    app.add_systems(
+        RapierPhysicsPlugin::<PhysicsHooks>::get_systems(PhysicsSet::StepSimulation)
+            .in_base_set(PhysicsSet::StepSimulation)
+            .in_schedule(CoreSchedule::FixedUpdate),
+    );
+    app.add_systems(
+        RapierPhysicsPlugin::<PhysicsHooks>::get_systems(PhysicsSet::Writeback)
+            .in_base_set(PhysicsSet::Writeback)
+            .in_schedule(CoreSchedule::FixedUpdate),
+    );
+
+    // Removals are tracked per frame, a frame without a step would lose them in the fixed schedule
+    app.add_system(sync_removals.in_base_set(CoreSet::PostUpdate));
+}
+
+pub trait FixedEventApp {
+    /// Adds an event of gameplay systems that is cleared once per fixed step, not once per frame,
+    /// so a reader in the next step does not miss it when frames are shorter than steps.
+    fn add_fixed_event<T: Event>(&mut self) -> &mut Self;
+}
+
+impl FixedEventApp for App {
+    fn add_fixed_event<T: Event>(&mut self) -> &mut Self {
+        if !self.world.contains_resource::<Events<T>>() {
+            self.init_resource::<Events<T>>().add_system(
+                Events::<T>::update_system
+                    .in_base_set(CoreSet::First)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::input::PlayerAction;
+    use crate::core::objects::shape::PlayerShape;
+    use bevy::prelude::*;
+
+    #[test]
+    fn same_inputs_give_same_transforms() {
+        let run = || {
+            let mut app = HeadlessApp::new();
+            app.build_scene(|builder| {
+                builder.spawn_wall_from_to(Vec2::new(-10.0, -1.0), Vec2::new(10.0, -2.0));
+                builder.spawn_player(Vec2::new(0.0, 1.0), 0, PlayerShape::Square);
+            });
+            app.script(0, &[PlayerAction::MoveLeft], SECOND / 2);
+            app.script(
+                0,
+                &[PlayerAction::Jump, PlayerAction::MoveRight],
+                SECOND / 4,
+            );
+            app.script(0, &[PlayerAction::SpinCW], SECOND / 4);
+            app.run(2 * SECOND);
+            app.get_player_transform(0)
+        };
+
+        let a = run();
+        let b = run();
+
+        assert_eq!(a.translation, b.translation);
+        assert_eq!(a.rotation, b.rotation);
+    }
+}
@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 use bevy_rapier2d::plugin::RapierConfiguration;
+use serde::Deserialize;
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Deserialize, Clone, Debug, Copy, PartialEq, Eq)]
 pub enum SceneDirection {
     Down,
     Right,
@@ -0,0 +1,175 @@
+use crate::core::deterministic::{DeterministicSettings, StepPerUpdate};
+use crate::core::input::{ActionState, PlayerAction};
+use crate::core::materials::bonus_material::BonusMaterial;
+use crate::core::materials::monster_material::MonsterMaterial;
+use crate::core::materials::player_material::PlayerMaterial;
+use crate::core::objects::player::Player;
+use crate::core::objects::shape::PlayerShapeVisualBundleCache;
+use crate::core::objects::side_effect::SideEffect;
+use crate::core::replay::{Replay, ReplayPlayback};
+use crate::core::scene_builder::scene_boundaries::SceneBoundaries;
+use crate::core::scene_builder::SceneBuilder;
+use crate::core::SimulationPlugin;
+use crate::states::GameWorldState;
+use bevy::ecs::event::ManualEventReader;
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+
+/// One second in default fixed steps.
+pub const SECOND: u32 = 60;
+
+/// Deterministic app with `SimulationPlugin` that needs no window or GPU.
+///
+/// Input of players is scripted through a replay, every `run` step is one fixed step.
+pub struct HeadlessApp {
+    pub app: App,
+}
+
+impl HeadlessApp {
+    pub fn new() -> Self {
+        Self::with_settings(DeterministicSettings::default())
+    }
+
+    pub fn with_settings(settings: DeterministicSettings) -> Self {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins);
+        app.add_plugin(TransformPlugin);
+        app.add_plugin(HierarchyPlugin);
+        app.add_plugin(bevy::input::InputPlugin);
+        app.add_plugin(AssetPlugin::default());
+        app.add_plugin(ScenePlugin);
+
+        // Assets that are usually added by the render plugins
+        app.add_asset::<Mesh>();
+        app.add_asset::<Image>();
+        app.add_asset::<ColorMaterial>();
+        app.add_asset::<PlayerMaterial>();
+        app.add_asset::<BonusMaterial>();
+        app.add_asset::<MonsterMaterial>();
+
+        app.add_state::<GameWorldState>();
+
+        app.insert_resource(ReplayPlayback::new(Replay::new(&settings), false));
+        app.insert_resource(settings);
+        app.insert_resource(StepPerUpdate);
+
+        app.add_plugin(SimulationPlugin);
+
+        HeadlessApp { app }
+    }
+
+    /// Builds the scene and starts the game world on the next step.
+    pub fn build_scene(&mut self, build: impl FnOnce(&mut SceneBuilder)) {
+        let mut state: SystemState<(
+            Commands,
+            Res<AssetServer>,
+            ResMut<Assets<Mesh>>,
+            ResMut<Assets<ColorMaterial>>,
+            ResMut<Assets<PlayerMaterial>>,
+            ResMut<Assets<BonusMaterial>>,
+            ResMut<Assets<MonsterMaterial>>,
+            ResMut<SceneBoundaries>,
+            ResMut<PlayerShapeVisualBundleCache>,
+        )> = SystemState::new(&mut self.app.world);
+
+        {
+            let (
+                commands,
+                asset_server,
+                meshes,
+                materials,
+                player_materials,
+                bonus_materials,
+                monster_materials,
+                boundaries,
+                psv,
+            ) = state.get_mut(&mut self.app.world);
+
+            let mut scene_builder = SceneBuilder::new(
+                commands,
+                asset_server,
+                meshes,
+                materials,
+                player_materials,
+                bonus_materials,
+                monster_materials,
+                boundaries,
+                psv,
+            );
+
+            build(&mut scene_builder);
+        }
+
+        state.apply(&mut self.app.world);
+
+        self.app
+            .world
+            .resource_mut::<NextState<GameWorldState>>()
+            .set(GameWorldState::GameWorld);
+    }
+
+    /// Holds `actions` for the next `steps` steps of the player script, after its previous actions.
+    pub fn script(&mut self, player_id: u32, actions: &[PlayerAction], steps: u32) {
+        let mut state = ActionState::default();
+        state.set_pressed(actions.iter().copied().collect());
+        let bits = state.get_pressed_bits();
+
+        let mut playback = self.app.world.resource_mut::<ReplayPlayback>();
+        let scripted: u32 = playback
+            .replay
+            .players
+            .get(&player_id)
+            .map_or(0, |runs| runs.iter().map(|(steps, _)| steps).sum());
+        let start = scripted.max(playback.step);
+
+        for step in start..start + steps {
+            playback.replay.push(player_id, step, bits);
+        }
+        // Playback waits at the end of the script until more actions are scripted
+        playback.finished = false;
+    }
+
+    pub fn run(&mut self, steps: u32) {
+        for _ in 0..steps {
+            self.app.update();
+        }
+    }
+
+    /// Runs `steps` steps and counts the events of type `E` sent meanwhile.
+    pub fn run_counting<E: Event>(&mut self, steps: u32) -> usize {
+        let mut reader = ManualEventReader::<E>::default();
+        let mut count = 0;
+        for _ in 0..steps {
+            self.app.update();
+            count += reader.iter(self.app.world.resource::<Events<E>>()).count();
+        }
+        count
+    }
+
+    pub fn get_player_entity(&mut self, player_id: u32) -> Entity {
+        self.app
+            .world
+            .query::<(Entity, &Player)>()
+            .iter(&self.app.world)
+            .find(|(_, player)| player.id == player_id)
+            .map(|(entity, _)| entity)
+            .expect("No player with this id")
+    }
+
+    pub fn get_player(&mut self, player_id: u32) -> Player {
+        let entity = self.get_player_entity(player_id);
+        self.app.world.get::<Player>(entity).unwrap().clone()
+    }
+
+    pub fn get_player_transform(&mut self, player_id: u32) -> Transform {
+        let entity = self.get_player_entity(player_id);
+        *self.app.world.get::<Transform>(entity).unwrap()
+    }
+
+    pub fn set_player_effect(&mut self, player_id: u32, side: usize, effect: SideEffect) {
+        let entity = self.get_player_entity(player_id);
+        self.app.world.get_mut::<Player>(entity).unwrap().effects[side] = effect;
+    }
+}
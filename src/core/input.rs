@@ -0,0 +1,264 @@
+use crate::core::objects::player::Player;
+use crate::core::replay::ReplayPlayback;
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::{HashMap, HashSet};
+use serde::Deserialize;
+
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<InputConfig>();
+        app.init_asset_loader::<InputConfigLoader>();
+
+        app.add_startup_system(load_input_config);
+        app.add_system(
+            update_player_actions
+                .in_set(InputSet)
+                .run_if(not(resource_exists::<ReplayPlayback>()))
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// Systems that fill `ActionState` of players, gameplay systems should run after it.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InputSet;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PlayerAction {
+    MoveLeft,
+    MoveRight,
+    SpinCW,
+    SpinCCW,
+    Jump,
+    DropDown,
+}
+
+impl PlayerAction {
+    pub const ALL: [PlayerAction; 6] = [
+        PlayerAction::MoveLeft,
+        PlayerAction::MoveRight,
+        PlayerAction::SpinCW,
+        PlayerAction::SpinCCW,
+        PlayerAction::Jump,
+        PlayerAction::DropDown,
+    ];
+}
+
+/// Actions of a single player for the current step.
+#[derive(Component, Clone, Debug, Default)]
+pub struct ActionState {
+    pressed: HashSet<PlayerAction>,
+    just_pressed: HashSet<PlayerAction>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: PlayerAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: PlayerAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// Pressed actions packed into bits in the `PlayerAction::ALL` order.
+    pub fn get_pressed_bits(&self) -> u8 {
+        PlayerAction::ALL
+            .iter()
+            .enumerate()
+            .filter(|(_, action)| self.pressed(**action))
+            .fold(0, |bits, (i, _)| bits | 1 << i)
+    }
+
+    pub fn set_pressed_bits(&mut self, bits: u8) {
+        let pressed = PlayerAction::ALL
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| bits & 1 << i != 0)
+            .map(|(_, action)| *action)
+            .collect();
+        self.set_pressed(pressed);
+    }
+
+    /// Replaces pressed actions, actions that were not pressed before become just pressed.
+    pub fn set_pressed(&mut self, pressed: HashSet<PlayerAction>) {
+        self.just_pressed = pressed.difference(&self.pressed).copied().collect();
+        self.pressed = pressed;
+    }
+}
+
+/// Input bindings loaded from `*.input.ron` files.
+#[derive(Deserialize, TypeUuid, Debug, Clone, PartialEq)]
+#[uuid = "a4f7c1e2-6d3b-4b8a-8e5f-0c9d2b7a1f64"]
+pub struct InputConfig {
+    // Bindings by `Player::id`
+    pub players: HashMap<u32, PlayerBindings>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PlayerBindings {
+    #[serde(default)]
+    pub keyboard: Option<KeyboardBindings>,
+    #[serde(default)]
+    pub gamepad: Option<GamepadBindings>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct KeyboardBindings {
+    pub keys: HashMap<PlayerAction, Vec<KeyCode>>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GamepadBindings {
+    // Gamepad with this id controls the player
+    pub id: usize,
+    #[serde(default)]
+    pub buttons: HashMap<PlayerAction, Vec<GamepadButtonType>>,
+    #[serde(default)]
+    pub axes: Vec<AxisBinding>,
+}
+
+/// Axis that triggers `negative` or `positive` action once deflected past `threshold`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct AxisBinding {
+    pub axis: GamepadAxisType,
+    #[serde(default)]
+    pub negative: Option<PlayerAction>,
+    #[serde(default)]
+    pub positive: Option<PlayerAction>,
+    #[serde(default = "default_threshold")]
+    pub threshold: f32,
+}
+
+fn default_threshold() -> f32 {
+    0.5
+}
+
+// Shipped config file is the only place where default bindings are listed
+impl Default for InputConfig {
+    fn default() -> Self {
+        ron::de::from_str(include_str!("../../assets/config/default.input.ron"))
+            .expect("Shipped input config is invalid")
+    }
+}
+
+#[derive(Default)]
+pub struct InputConfigLoader;
+
+impl AssetLoader for InputConfigLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let config = ron::de::from_bytes::<InputConfig>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(config));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["input.ron"]
+    }
+}
+
+/// Input config in use, default bindings are used until it is loaded.
+#[derive(Resource, Debug, Clone)]
+pub struct CurrentInputConfig {
+    pub handle: Handle<InputConfig>,
+    pub fallback: InputConfig,
+}
+
+fn load_input_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CurrentInputConfig {
+        handle: asset_server.load("config/default.input.ron"),
+        fallback: InputConfig::default(),
+    });
+}
+
+fn update_player_actions(
+    mut players: Query<(&Player, &mut ActionState)>,
+    current_config: Option<Res<CurrentInputConfig>>,
+    configs: Res<Assets<InputConfig>>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<Gamepads>,
+) {
+    let Some(current_config) = current_config else {
+        return;
+    };
+
+    let config = configs
+        .get(&current_config.handle)
+        .unwrap_or(&current_config.fallback);
+
+    for (player, mut actions) in players.iter_mut() {
+        let mut pressed = HashSet::new();
+
+        if let Some(bindings) = config.players.get(&player.id) {
+            if let Some(keyboard) = &bindings.keyboard {
+                for (action, codes) in &keyboard.keys {
+                    if keys.any_pressed(codes.iter().copied()) {
+                        pressed.insert(*action);
+                    }
+                }
+            }
+
+            if let Some(gamepad_bindings) = &bindings.gamepad {
+                let gamepad = Gamepad::new(gamepad_bindings.id);
+                if gamepads.contains(gamepad) {
+                    for (action, types) in &gamepad_bindings.buttons {
+                        if types
+                            .iter()
+                            .any(|t| buttons.pressed(GamepadButton::new(gamepad, *t)))
+                        {
+                            pressed.insert(*action);
+                        }
+                    }
+
+                    for binding in &gamepad_bindings.axes {
+                        let value = axes
+                            .get(GamepadAxis::new(gamepad, binding.axis))
+                            .unwrap_or(0.0);
+                        if value <= -binding.threshold {
+                            pressed.extend(binding.negative);
+                        }
+                        if value >= binding.threshold {
+                            pressed.extend(binding.positive);
+                        }
+                    }
+                }
+            }
+        }
+
+        actions.set_pressed(pressed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_matches_shipped_file() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/assets/config/default.input.ron"
+        );
+        let shipped: InputConfig = ron::de::from_bytes(&std::fs::read(path).unwrap()).unwrap();
+        let config = InputConfig::default();
+
+        assert_eq!(config, shipped);
+        for id in [0, 1] {
+            let keys = &config.players[&id].keyboard.as_ref().unwrap().keys;
+            assert!(PlayerAction::ALL
+                .iter()
+                .all(|action| keys.contains_key(action)));
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use crate::core::materials::bonus_material::BonusMaterial;
+use crate::core::materials::monster_material::MonsterMaterial;
+use crate::core::materials::player_material::PlayerMaterial;
+use crate::core::objects::gravity_field::GravityFieldKind;
+use crate::core::objects::gravity_switch::GravityChange;
+use crate::core::objects::hazard::HazardKind;
+use crate::core::objects::monster::{MonsterKind, PatrolBounds};
+use crate::core::objects::platform::PlatformPath;
+use crate::core::objects::shape::{PlayerShape, PlayerShapeVisualBundleCache};
+use crate::core::objects::side_effect::SideEffect;
+use crate::core::objects::spawner::{SpawnerTrigger, Wave};
+use crate::core::scene_builder::scene_boundaries::SceneBoundaries;
+use crate::core::scene_builder::SceneBuilder;
+use crate::states::GameWorldState;
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use serde::Deserialize;
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Level>();
+        app.init_asset_loader::<LevelLoader>();
+
+        app.add_system(spawn_current_level.run_if(in_state(GameWorldState::None)));
+    }
+}
+
+/// Level description loaded from `*.level.ron` files.
+///
+/// Every list is optional, so a level file only has to mention what it uses.
+#[derive(Deserialize, TypeUuid, Debug, Clone, Default)]
+#[uuid = "5c3e1a7d-2b8f-4f6e-9d41-7a0c2e9b6f13"]
+pub struct Level {
+    #[serde(default)]
+    pub boundaries: Option<LevelBoundaries>,
+    #[serde(default)]
+    pub view_range: Option<f32>,
+    #[serde(default)]
+    pub max_view_range: Option<f32>,
+    #[serde(default)]
+    pub players: Vec<LevelPlayer>,
+    #[serde(default)]
+    pub walls: Vec<LevelWall>,
+    #[serde(default)]
+    pub boxes: Vec<LevelBox>,
+    #[serde(default)]
+    pub polygons: Vec<LevelPolygon>,
+    #[serde(default)]
+    pub polylines: Vec<LevelPolyline>,
+    #[serde(default)]
+    pub slopes: Vec<LevelSlope>,
+    #[serde(default)]
+    pub one_way_platforms: Vec<LevelOneWayPlatform>,
+    #[serde(default)]
+    pub hazards: Vec<LevelHazard>,
+    #[serde(default)]
+    pub bonuses: Vec<LevelBonus>,
+    #[serde(default)]
+    pub monsters: Vec<LevelMonster>,
+    #[serde(default)]
+    pub spawners: Vec<LevelSpawner>,
+    #[serde(default)]
+    pub encounters: Vec<LevelEncounter>,
+    #[serde(default)]
+    pub shape_bonuses: Vec<LevelShapeBonus>,
+    #[serde(default)]
+    pub doors: Vec<LevelDoor>,
+    #[serde(default)]
+    pub laser_receivers: Vec<LevelLaserReceiver>,
+    #[serde(default)]
+    pub gravity_switches: Vec<LevelGravitySwitch>,
+    #[serde(default)]
+    pub gravity_fields: Vec<LevelGravityField>,
+    #[serde(default)]
+    pub moving_platforms: Vec<LevelMovingPlatform>,
+    #[serde(default)]
+    pub rotating_platforms: Vec<LevelRotatingPlatform>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct LevelBoundaries {
+    pub left: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub top: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelPlayer {
+    pub position: Vec2,
+    #[serde(default)]
+    pub id: u32,
+    #[serde(default)]
+    pub shape: PlayerShape,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelWall {
+    pub from: Vec2,
+    pub to: Vec2,
+    // Visible and solid only while lit by a flashlight.
+    #[serde(default)]
+    pub dark: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelBox {
+    pub position: Vec2,
+    pub size: Vec2,
+    // Radians, counter-clockwise
+    #[serde(default)]
+    pub angle: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelPolygon {
+    pub points: Vec<Vec2>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelPolyline {
+    pub points: Vec<Vec2>,
+    #[serde(default = "default_polyline_thickness")]
+    pub thickness: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelSlope {
+    pub from: Vec2,
+    pub to: Vec2,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelOneWayPlatform {
+    pub from: Vec2,
+    pub to: Vec2,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelHazard {
+    pub from: Vec2,
+    pub to: Vec2,
+    pub kind: HazardKind,
+}
+
+fn default_polyline_thickness() -> f32 {
+    0.1
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelBonus {
+    pub position: Vec2,
+    pub effect: SideEffect,
+    #[serde(default)]
+    pub dark: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelShapeBonus {
+    pub position: Vec2,
+    pub shape: PlayerShape,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelMonster {
+    pub position: Vec2,
+    #[serde(default)]
+    pub kind: MonsterKind,
+    // Monster walks back and forth instead of standing still until it sees a player.
+    #[serde(default = "default_patrol")]
+    pub patrol: bool,
+    // Patrol stays between these points instead of walking the whole platform.
+    #[serde(default)]
+    pub patrol_bounds: Option<PatrolBounds>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelSpawner {
+    pub position: Vec2,
+    // Encounter waves refer to the spawner by this id, spawners without it are never in a wave
+    #[serde(default)]
+    pub id: Option<u32>,
+    #[serde(default)]
+    pub kind: MonsterKind,
+    #[serde(default)]
+    pub patrol: bool,
+    pub trigger: SpawnerTrigger,
+    #[serde(default = "default_max_alive")]
+    pub max_alive: usize,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelEncounter {
+    #[serde(default)]
+    pub id: u32,
+    // Corners of the arena, players inside start the encounter
+    pub from: Vec2,
+    pub to: Vec2,
+    // Doors with the same channel are locked during the encounter
+    pub channel: u32,
+    pub waves: Vec<Wave>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelDoor {
+    pub from: Vec2,
+    pub to: Vec2,
+    pub channel: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelLaserReceiver {
+    pub position: Vec2,
+    // Doors with the same channel open while the receiver is hit
+    pub channel: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelGravitySwitch {
+    pub position: Vec2,
+    pub change: GravityChange,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelGravityField {
+    pub position: Vec2,
+    pub kind: GravityFieldKind,
+    #[serde(default = "default_gravity_strength")]
+    pub strength: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelMovingPlatform {
+    pub size: Vec2,
+    pub path: PlatformPath,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelRotatingPlatform {
+    pub position: Vec2,
+    pub size: Vec2,
+    // Radians per second, counter-clockwise
+    pub angular_speed: f32,
+}
+
+fn default_gravity_strength() -> f32 {
+    9.8
+}
+
+fn default_patrol() -> bool {
+    true
+}
+
+fn default_max_alive() -> usize {
+    3
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let level = ron::de::from_bytes::<Level>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(level));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+/// Level that should be built as soon as its asset is loaded.
+#[derive(Resource, Debug, Clone)]
+pub struct CurrentLevel {
+    pub handle: Handle<Level>,
+    pub spawned: bool,
+}
+
+impl CurrentLevel {
+    pub fn new(handle: Handle<Level>) -> Self {
+        CurrentLevel {
+            handle,
+            spawned: false,
+        }
+    }
+}
+
+fn spawn_current_level(
+    commands: Commands,
+    asset_server: Res<AssetServer>,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<ColorMaterial>>,
+    player_materials: ResMut<Assets<PlayerMaterial>>,
+    bonus_materials: ResMut<Assets<BonusMaterial>>,
+    monster_materials: ResMut<Assets<MonsterMaterial>>,
+    boundaries: ResMut<SceneBoundaries>,
+    psv: ResMut<PlayerShapeVisualBundleCache>,
+    levels: Res<Assets<Level>>,
+    current_level: Option<ResMut<CurrentLevel>>,
+    mut next_game_state: ResMut<NextState<GameWorldState>>,
+) {
+    let Some(mut current_level) = current_level else {
+        return;
+    };
+
+    if current_level.spawned {
+        return;
+    }
+
+    let Some(level) = levels.get(&current_level.handle) else {
+        return;
+    };
+
+    let mut scene_builder = SceneBuilder::new(
+        commands,
+        asset_server,
+        meshes,
+        materials,
+        player_materials,
+        bonus_materials,
+        monster_materials,
+        boundaries,
+        psv,
+    );
+
+    scene_builder.spawn_level(level);
+
+    current_level.spawned = true;
+    next_game_state.set(GameWorldState::GameWorld);
+}
@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use bevy::reflect::TypeUuid;
 
+use crate::core::objects::side_effect::SideEffect;
+
 use bevy::render::render_resource::{AsBindGroup, ShaderRef};
 use bevy::sprite::Material2d;
 
@@ -21,6 +23,42 @@ pub struct MonsterMaterial {
     pub texture: Option<Handle<Image>>,
 }
 
+// NOTE: These must match the bits read in monster_material.wgsl!
+bitflags::bitflags! {
+    #[repr(transparent)]
+    pub struct MonsterStateFlags: u32 {
+        const FACING_LEFT       = (1 << 0);
+        const HURT              = (1 << 4);
+        const DYING             = (1 << 5);
+        const NONE              = 0;
+    }
+}
+
+impl MonsterStateFlags {
+    // Death animation progress (0..=255) is stored in bits 8..16 of the state
+    pub const PROGRESS_SHIFT: u32 = 8;
+
+    // Index of `MonsterState` is stored in bits 16..19 of the state
+    pub const AI_STATE_SHIFT: u32 = 16;
+
+    pub fn with_progress(self, progress: f32) -> u32 {
+        self.bits() | (((progress.clamp(0.0, 1.0) * 255.0) as u32) << Self::PROGRESS_SHIFT)
+    }
+
+    pub fn with_ai_state(self, index: u32) -> u32 {
+        self.bits() | (index & 7) << Self::AI_STATE_SHIFT
+    }
+
+    // Index of the side effect carried by a thief is stored in bits 20..23 of the state
+    pub const STOLEN_EFFECT_SHIFT: u32 = 20;
+
+    pub fn get_stolen_effect_bits(effect: Option<SideEffect>) -> u32 {
+        effect.map_or(0, |effect| {
+            (effect.to_index() & 7) << Self::STOLEN_EFFECT_SHIFT
+        })
+    }
+}
+
 impl Material2d for MonsterMaterial {
     fn fragment_shader() -> ShaderRef {
         "shaders/monster_material.wgsl".into()
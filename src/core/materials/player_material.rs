@@ -12,6 +12,7 @@ use bevy::sprite::Material2d;
 pub struct PlayerMaterial {
     pub color: Color,
     pub effect_index: [u32; MAX_SIDES],
+    pub number_of_sides: u32,
 
     #[texture(1)]
     #[sampler(2)]
@@ -36,6 +37,7 @@ pub struct PlayerMaterialUniform {
     pub color: Vec4,
     pub effect_index: [UVec4; MAX_SIDES],
     pub flags: u32,
+    pub number_of_sides: u32,
 }
 
 // NOTE: These must match the bit flags in bevy_sprite/src/mesh2d/color_material.wgsl!
@@ -67,6 +69,7 @@ impl AsBindGroupShaderType<PlayerMaterialUniform> for PlayerMaterial {
             color: self.color.as_linear_rgba_f32().into(),
             effect_index: self.effect_index.clone().map(|i| UVec4::splat(i)),
             flags: flags.bits(),
+            number_of_sides: self.number_of_sides,
         }
     }
 }
@@ -2,40 +2,58 @@ use crate::core::materials::bonus_material::BonusMaterial;
 use crate::core::materials::monster_material::MonsterMaterial;
 use crate::core::materials::player_material::PlayerMaterial;
 use crate::core::materials::update_materials;
+use crate::core::objects::door::DoorPlugin;
+use crate::core::objects::effect_thief::EffectThiefPlugin;
+use crate::core::objects::flashlight::FlashlightPlugin;
+use crate::core::objects::gravity_field::GravityFieldPlugin;
+use crate::core::objects::gravity_switch::GravitySwitchPlugin;
+use crate::core::objects::hazard::HazardPlugin;
+use crate::core::objects::health::HealthPlugin;
+use crate::core::objects::laser::LaserPlugin;
 use crate::core::objects::monster::MonsterPlugin;
+use crate::core::objects::monster_ai::MonsterAiPlugin;
+use crate::core::objects::navigation::NavigationPlugin;
+use crate::core::objects::one_way_platform::GamePhysicsHooks;
+use crate::core::objects::platform::PlatformPlugin;
+use crate::core::objects::respawn::RespawnPlugin;
 use crate::core::objects::shape::ShapePlugin;
+use crate::core::objects::spawner::SpawnerPlugin;
 use crate::states::GameWorldState;
 use bevy::prelude::*;
 use bevy::sprite::Material2dPlugin;
+use bevy::time::TimeSystem;
 use bevy_prototype_debug_lines::DebugLinesPlugin;
 use bevy_rapier2d::prelude::*;
 use objects::player::PlayerPlugin;
 
 use self::camera::CameraPlugin;
+use self::deterministic::{
+    add_fixed_physics_systems, configure_fixed_schedule, get_timestep_mode, step_per_frame,
+    step_per_update, DeterministicSettings, FixedEventApp, GameRng, StepPerUpdate,
+};
+use self::input::InputPlugin;
+use self::level::LevelPlugin;
 use self::objects::bonus::BonusPlugin;
+use self::replay::ReplayPlugin;
 use self::scene_builder::scene_boundaries::SceneBoundaries;
 
 pub mod camera;
+pub mod deterministic;
 pub mod direction;
+pub mod headless;
+pub mod input;
+pub mod level;
 pub mod materials;
 pub mod objects;
+pub mod replay;
 pub mod scene_builder;
 
 pub struct CorePlugin;
 
 impl Plugin for CorePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(RapierConfiguration {
-            timestep_mode: TimestepMode::Variable {
-                max_dt: 1.0 / 30.0,
-                time_scale: 1.0,
-                substeps: 5,
-            },
-            gravity: Vec2::NEG_Y * 9.8,
-            ..default()
-        });
+        app.add_plugin(SimulationPlugin);
 
-        app.add_plugin(RapierPhysicsPlugin::<NoUserData>::default());
         app.add_plugin(RapierDebugRenderPlugin {
             style: DebugRenderStyle {
                 rigid_body_axes_length: 0.3,
@@ -44,16 +62,87 @@ impl Plugin for CorePlugin {
             ..default()
         });
 
+        app.add_plugin(DebugLinesPlugin::default());
+
         app.add_plugin(Material2dPlugin::<PlayerMaterial>::default());
         app.add_plugin(Material2dPlugin::<BonusMaterial>::default());
         app.add_plugin(Material2dPlugin::<MonsterMaterial>::default());
 
+        app.add_plugin(CameraPlugin);
+    }
+}
+
+/// Game logic and physics without rendering, windows or cameras.
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        let deterministic = app.world.get_resource::<DeterministicSettings>().cloned();
+
+        app.insert_resource(GameRng::new(deterministic.as_ref()));
+
+        // Collision events are read by gameplay systems of the next step
+        app.add_fixed_event::<CollisionEvent>();
+        configure_fixed_schedule(app);
+
+        match &deterministic {
+            Some(settings) => {
+                app.insert_resource(RapierConfiguration {
+                    timestep_mode: get_timestep_mode(settings),
+                    gravity: Vec2::NEG_Y * 9.8,
+                    ..default()
+                });
+                app.insert_resource(FixedTime::new_from_secs(settings.timestep));
+                app.add_plugin(
+                    RapierPhysicsPlugin::<GamePhysicsHooks>::default()
+                        .with_default_system_setup(false),
+                );
+                add_fixed_physics_systems::<GamePhysicsHooks>(app);
+
+                app.add_system(
+                    step_per_update
+                        .in_base_set(CoreSet::First)
+                        .before(TimeSystem)
+                        .run_if(resource_exists::<StepPerUpdate>()),
+                );
+            }
+            None => {
+                app.insert_resource(RapierConfiguration {
+                    timestep_mode: TimestepMode::Variable {
+                        max_dt: 1.0 / 30.0,
+                        time_scale: 1.0,
+                        substeps: 5,
+                    },
+                    gravity: Vec2::NEG_Y * 9.8,
+                    ..default()
+                });
+                app.add_plugin(RapierPhysicsPlugin::<GamePhysicsHooks>::default());
+
+                app.add_system(step_per_frame.in_base_set(CoreSet::First).after(TimeSystem));
+            }
+        }
+
+        app.add_plugin(InputPlugin);
+        app.add_plugin(ReplayPlugin);
         app.add_plugin(PlayerPlugin);
         app.add_plugin(MonsterPlugin);
+        app.add_plugin(MonsterAiPlugin);
+        app.add_plugin(NavigationPlugin);
+        app.add_plugin(SpawnerPlugin);
+        app.add_plugin(EffectThiefPlugin);
         app.add_plugin(ShapePlugin);
         app.add_plugin(BonusPlugin);
+        app.add_plugin(HealthPlugin);
+        app.add_plugin(HazardPlugin);
+        app.add_plugin(RespawnPlugin);
+        app.add_plugin(FlashlightPlugin);
+        app.add_plugin(LaserPlugin);
+        app.add_plugin(DoorPlugin);
+        app.add_plugin(GravitySwitchPlugin);
+        app.add_plugin(GravityFieldPlugin);
+        app.add_plugin(PlatformPlugin);
 
-        app.add_plugin(CameraPlugin);
+        app.add_plugin(LevelPlugin);
 
         app.init_resource::<SceneBoundaries>();
 
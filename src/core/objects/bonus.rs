@@ -3,12 +3,19 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::CollisionEvent;
 
 use super::player::Player;
+use super::shape::{PlayerShape, ShapeShift};
 
 pub struct BonusPlugin;
 
 impl Plugin for BonusPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems((bonus_pickup.run_if(in_state(GameWorldState::GameWorld)),));
+        app.add_systems(
+            (
+                bonus_pickup.run_if(in_state(GameWorldState::GameWorld)),
+                shape_bonus_pickup.run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
     }
 }
 
@@ -45,6 +52,18 @@ impl Bonus {
     }
 }
 
+/// Bonus that turns the player into another shape.
+#[derive(Component, Clone, Debug, Default)]
+pub struct ShapeBonus {
+    pub shape: Option<PlayerShape>,
+}
+
+impl ShapeBonus {
+    pub fn is_used(&self) -> bool {
+        self.shape.is_none()
+    }
+}
+
 pub fn bonus_pickup(
     mut commands: Commands,
     mut collision_events: EventReader<CollisionEvent>,
@@ -71,3 +90,34 @@ pub fn bonus_pickup(
         }
     }
 }
+
+pub fn shape_bonus_pickup(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    players: Query<&Player>,
+    mut bonuses: Query<(Entity, &mut ShapeBonus)>,
+) {
+    for collision_event in collision_events.iter() {
+        if let CollisionEvent::Started(a, b, _args) = collision_event {
+            let (player, bonus) = if players.contains(*a) {
+                (*a, *b)
+            } else if players.contains(*b) {
+                (*b, *a)
+            } else {
+                continue;
+            };
+
+            if let Ok((_, mut bonus)) = bonuses.get_mut(bonus) {
+                if let Some(shape) = bonus.shape.take() {
+                    commands.entity(player).insert(ShapeShift { shape });
+                }
+            }
+        }
+    }
+
+    for (entity, bonus) in &bonuses {
+        if bonus.is_used() {
+            commands.entity(entity).despawn()
+        }
+    }
+}
@@ -5,13 +5,41 @@ pub const WALL_BIT: Group = Group::GROUP_1;
 pub const PLAYER_BIT: Group = Group::GROUP_2;
 pub const BONUS_BIT: Group = Group::GROUP_3;
 pub const MONSTER_BIT: Group = Group::GROUP_4;
+pub const DARK_BIT: Group = Group::GROUP_5;
+pub const ONE_WAY_BIT: Group = Group::GROUP_6;
 
 pub const WALL_FILTER: Group = PLAYER_BIT.union(BONUS_BIT).union(MONSTER_BIT);
-pub const PLAYER_FILTER: Group = WALL_BIT.union(BONUS_BIT).union(MONSTER_BIT);
+pub const PLAYER_FILTER: Group = WALL_BIT
+    .union(BONUS_BIT)
+    .union(MONSTER_BIT)
+    .union(ONE_WAY_BIT);
 pub const BONUS_FILTER: Group = WALL_BIT.union(PLAYER_BIT).union(BONUS_BIT);
-pub const MONSTER_FILTER: Group = WALL_BIT.union(PLAYER_BIT);
+pub const MONSTER_FILTER: Group = WALL_BIT.union(PLAYER_BIT).union(ONE_WAY_BIT);
+pub const ONE_WAY_FILTER: Group = PLAYER_BIT.union(MONSTER_BIT);
 
 pub const WALL_CG: CollisionGroups = CollisionGroups::new(WALL_BIT, WALL_FILTER);
 pub const PLAYER_CG: CollisionGroups = CollisionGroups::new(PLAYER_BIT, PLAYER_FILTER);
 pub const BONUS_CG: CollisionGroups = CollisionGroups::new(BONUS_BIT, BONUS_FILTER);
 pub const MONSTER_CG: CollisionGroups = CollisionGroups::new(MONSTER_BIT, MONSTER_FILTER);
+// One-way platforms stop players and monsters only, light and lasers pass through them.
+pub const ONE_WAY_CG: CollisionGroups = CollisionGroups::new(ONE_WAY_BIT, ONE_WAY_FILTER);
+// Lava is a sensor volume for players only, rays pass through it like through bonuses.
+pub const LAVA_CG: CollisionGroups = CollisionGroups::new(BONUS_BIT, PLAYER_BIT);
+
+// Dark objects collide with nothing until lit, but can still be found by queries with DARK_CG.
+pub const DARK_CG: CollisionGroups = CollisionGroups::new(DARK_BIT, DARK_BIT);
+pub const DARK_WALL_LIT_CG: CollisionGroups =
+    CollisionGroups::new(WALL_BIT.union(DARK_BIT), WALL_FILTER.union(DARK_BIT));
+pub const DARK_BONUS_LIT_CG: CollisionGroups =
+    CollisionGroups::new(BONUS_BIT.union(DARK_BIT), BONUS_FILTER.union(DARK_BIT));
+
+// Query groups for light, it is blocked by walls only.
+pub const LIGHT_CG: CollisionGroups = CollisionGroups::new(PLAYER_BIT, WALL_BIT);
+// Query groups for laser beams, they stop at walls and monsters.
+pub const LASER_CG: CollisionGroups = CollisionGroups::new(PLAYER_BIT, WALL_BIT.union(MONSTER_BIT));
+// Query groups for patrol rays, monsters turn at walls and stand on one-way platforms too.
+pub const PATROL_WALL_CG: CollisionGroups = CollisionGroups::new(MONSTER_BIT, WALL_BIT);
+pub const PATROL_GROUND_CG: CollisionGroups =
+    CollisionGroups::new(MONSTER_BIT, WALL_BIT.union(ONE_WAY_BIT));
+// Query groups for monster line of sight, only walls block it.
+pub const SIGHT_CG: CollisionGroups = CollisionGroups::new(MONSTER_BIT, WALL_BIT);
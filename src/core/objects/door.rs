@@ -0,0 +1,67 @@
+use crate::core::objects::collision_groups::{WALL_BIT, WALL_CG};
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier2d::prelude::*;
+
+pub struct DoorPlugin;
+
+impl Plugin for DoorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DoorChannels>();
+        app.add_systems(
+            (update_doors.run_if(in_state(GameWorldState::GameWorld)),)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// Wall that disappears while its channel is open.
+#[derive(Component, Clone, Debug, Default)]
+pub struct Door {
+    pub channel: u32,
+    pub open: bool,
+}
+
+/// Open state of door channels, written by switches and read by doors.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct DoorChannels {
+    pub open: HashMap<u32, bool>,
+}
+
+impl DoorChannels {
+    pub fn set(&mut self, channel: u32, open: bool) {
+        if self.is_open(channel) != open {
+            self.open.insert(channel, open);
+        }
+    }
+
+    pub fn is_open(&self, channel: u32) -> bool {
+        self.open.get(&channel).copied().unwrap_or(false)
+    }
+}
+
+fn update_doors(
+    channels: Res<DoorChannels>,
+    mut doors: Query<(&mut Door, &mut Visibility, &mut CollisionGroups)>,
+) {
+    if !channels.is_changed() {
+        return;
+    }
+
+    for (mut door, mut visibility, mut groups) in doors.iter_mut() {
+        let open = channels.is_open(door.channel);
+        if door.open == open {
+            continue;
+        }
+
+        door.open = open;
+        if open {
+            *visibility = Visibility::Hidden;
+            *groups = CollisionGroups::new(WALL_BIT, Group::NONE);
+        } else {
+            *visibility = Visibility::Inherited;
+            *groups = WALL_CG;
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::core::deterministic::FixedEventApp;
+use crate::core::materials::bonus_material::BonusMaterial;
+use crate::core::materials::monster_material::MonsterMaterial;
+use crate::core::materials::player_material::PlayerMaterial;
+use crate::core::objects::health::{monster_contact_damage, player_contacts, PlayerColliders};
+use crate::core::objects::monster::{
+    kill_monsters, Monster, MonsterDying, MonsterKilled, MonsterKind,
+};
+use crate::core::objects::player::Player;
+use crate::core::objects::shape::PlayerShapeVisualBundleCache;
+use crate::core::objects::side_effect::SideEffect;
+use crate::core::scene_builder::scene_boundaries::SceneBoundaries;
+use crate::core::scene_builder::SceneBuilder;
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+pub struct EffectThiefPlugin;
+
+impl Plugin for EffectThiefPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_fixed_event::<EffectStolen>();
+
+        app.add_systems(
+            (
+                steal_effects
+                    .after(monster_contact_damage)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+                drop_stolen_effects
+                    .after(kill_monsters)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// Thief took the side effect from a player side.
+#[derive(Clone, Debug)]
+pub struct EffectStolen {
+    pub player: Entity,
+    pub thief: Entity,
+    pub side: usize,
+    pub effect: SideEffect,
+}
+
+fn steal_effects(
+    mut thieves: Query<(Entity, &mut Monster), Without<MonsterDying>>,
+    mut players: Query<(&Transform, &mut Player)>,
+    player_colliders: PlayerColliders,
+    context: Res<RapierContext>,
+    mut stolen_events: EventWriter<EffectStolen>,
+) {
+    for (thief_entity, mut thief) in thieves.iter_mut() {
+        // Thief carries one effect at a time
+        if thief.kind != MonsterKind::Thief || thief.stolen_effect.is_some() {
+            continue;
+        }
+
+        for (player_entity, normal) in player_contacts(&context, thief_entity, &player_colliders) {
+            let Ok((transform, mut player)) = players.get_mut(player_entity) else {
+                continue;
+            };
+
+            let side = player.get_side_facing(transform, normal);
+            let effect = player.effects[side];
+
+            if effect == SideEffect::None {
+                continue;
+            }
+
+            player.effects[side] = SideEffect::None;
+            thief.stolen_effect = Some(effect);
+            stolen_events.send(EffectStolen {
+                player: player_entity,
+                thief: thief_entity,
+                side,
+                effect,
+            });
+            break;
+        }
+    }
+}
+
+fn drop_stolen_effects(
+    commands: Commands,
+    asset_server: Res<AssetServer>,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<ColorMaterial>>,
+    player_materials: ResMut<Assets<PlayerMaterial>>,
+    bonus_materials: ResMut<Assets<BonusMaterial>>,
+    monster_materials: ResMut<Assets<MonsterMaterial>>,
+    boundaries: ResMut<SceneBoundaries>,
+    psv: ResMut<PlayerShapeVisualBundleCache>,
+    mut killed_events: EventReader<MonsterKilled>,
+    mut monsters: Query<(&mut Monster, &Transform)>,
+) {
+    if killed_events.is_empty() {
+        return;
+    }
+
+    let mut scene_builder = SceneBuilder::new(
+        commands,
+        asset_server,
+        meshes,
+        materials,
+        player_materials,
+        bonus_materials,
+        monster_materials,
+        boundaries,
+        psv,
+    );
+
+    for killed in killed_events.iter() {
+        let Ok((mut monster, transform)) = monsters.get_mut(killed.monster) else {
+            continue;
+        };
+
+        if let Some(effect) = monster.stolen_effect.take() {
+            scene_builder.spawn_effect_bonus(transform.translation.truncate(), effect);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::objects::bonus::Bonus;
+    use crate::core::objects::health::Health;
+    use crate::core::objects::shape::PlayerShape;
+
+    #[test]
+    fn thief_steals_effect_and_drops_it_on_death() {
+        let mut app = HeadlessApp::new();
+        let mut thief = None;
+        app.build_scene(|builder| {
+            builder.spawn_wall_from_to(Vec2::new(-10.0, -1.0), Vec2::new(10.0, -2.0));
+            builder.spawn_player(Vec2::new(0.0, -0.5), 0, PlayerShape::Square);
+            thief = Some(builder.spawn_monster(Vec2::new(3.0, -0.5), MonsterKind::Thief, false));
+        });
+        app.run(1);
+        // Side 1 of the square looks right, to the thief
+        app.set_player_effect(0, 1, SideEffect::Sticky);
+        app.run(3 * SECOND);
+
+        let thief = thief.unwrap();
+        assert_eq!(app.get_player(0).effects[1], SideEffect::None);
+        assert_eq!(
+            app.app.world.get::<Monster>(thief).unwrap().stolen_effect,
+            Some(SideEffect::Sticky)
+        );
+
+        app.app.world.get_mut::<Health>(thief).unwrap().current = 0.0;
+        app.run(2);
+
+        let mut bonuses = app.app.world.query::<&Bonus>();
+        assert!(bonuses
+            .iter(&app.app.world)
+            .any(|bonus| bonus.effect == Some(SideEffect::Sticky)));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::core::objects::collision_groups::{DARK_CG, LIGHT_CG};
+use crate::core::objects::player::Player;
+use crate::core::objects::side_effect::SideEffect;
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+
+pub struct FlashlightPlugin;
+
+impl Plugin for FlashlightPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                update_flashlights.run_if(in_state(GameWorldState::GameWorld)),
+                reveal_dark_objects
+                    .after(update_flashlights)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+
+        app.add_system(
+            draw_flashlights
+                .run_if(resource_exists::<DebugLines>())
+                .run_if(in_state(GameWorldState::GameWorld)),
+        );
+    }
+}
+
+/// Light cones of all player sides with `SideEffect::Flashlight`.
+#[derive(Component, Clone, Debug, Default)]
+pub struct Flashlight {
+    pub cones: Vec<LightCone>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LightCone {
+    pub side: usize,
+    pub origin: Vec2,
+    pub direction: Vec2,
+    // Rays from the left edge of the cone to the right one, clipped by walls
+    pub rays: Vec<LightRay>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct LightRay {
+    pub direction: Vec2,
+    pub length: f32,
+}
+
+impl LightRay {
+    pub fn end(&self, origin: Vec2) -> Vec2 {
+        origin + self.direction * self.length
+    }
+}
+
+impl LightCone {
+    pub const HALF_ANGLE: f32 = 0.45;
+    pub const RANGE: f32 = 6.0;
+    pub const RAYS: usize = 24;
+
+    /// Checks that point is inside the cone and not hidden behind a wall.
+    pub fn contains(&self, point: Vec2) -> bool {
+        let offset = point - self.origin;
+        let distance = offset.length();
+        if distance < 1e-4 {
+            return true;
+        }
+
+        let angle = self.direction.angle_between(offset);
+        if angle.abs() > Self::HALF_ANGLE || self.rays.len() < 2 {
+            return false;
+        }
+
+        // Rays are evenly spread from -HALF_ANGLE to HALF_ANGLE
+        let t =
+            (angle + Self::HALF_ANGLE) / (2.0 * Self::HALF_ANGLE) * (self.rays.len() - 1) as f32;
+        let i = (t.floor() as usize).min(self.rays.len() - 2);
+        let f = t - i as f32;
+        let length = self.rays[i].length * (1.0 - f) + self.rays[i + 1].length * f;
+
+        distance <= length
+    }
+}
+
+/// Object that is invisible and does not collide until some flashlight lights it.
+#[derive(Component, Clone, Debug)]
+pub struct Dark {
+    pub lit: bool,
+    // Collision groups to use while lit
+    pub lit_groups: CollisionGroups,
+}
+
+impl Dark {
+    pub fn new(lit_groups: CollisionGroups) -> Self {
+        Dark {
+            lit: false,
+            lit_groups,
+        }
+    }
+}
+
+fn update_flashlights(
+    mut commands: Commands,
+    mut players: Query<(Entity, &Player, &Transform, Option<&mut Flashlight>)>,
+    context: Res<RapierContext>,
+) {
+    for (entity, player, transform, flashlight) in players.iter_mut() {
+        let mut cones = vec![];
+
+        let centers = player.get_side_centers();
+        let directions = player.get_side_directions();
+
+        for i in 0..centers.len() {
+            if player.effects[i] != SideEffect::Flashlight {
+                continue;
+            }
+
+            let origin = transform.transform_point(centers[i].extend(0.0)).truncate();
+            let direction = (transform.rotation * directions[i].extend(0.0))
+                .truncate()
+                .normalize();
+
+            let rays = (0..LightCone::RAYS)
+                .map(|r| {
+                    let angle = -LightCone::HALF_ANGLE
+                        + 2.0 * LightCone::HALF_ANGLE * r as f32 / (LightCone::RAYS - 1) as f32;
+                    let ray_direction = Vec2::from_angle(angle).rotate(direction);
+                    let length = context
+                        .cast_ray(
+                            origin,
+                            ray_direction,
+                            LightCone::RANGE,
+                            true,
+                            QueryFilter::new().groups(LIGHT_CG),
+                        )
+                        .map_or(LightCone::RANGE, |(_, toi)| toi);
+                    LightRay {
+                        direction: ray_direction,
+                        length,
+                    }
+                })
+                .collect();
+
+            cones.push(LightCone {
+                side: i,
+                origin,
+                direction,
+                rays,
+            });
+        }
+
+        if let Some(mut flashlight) = flashlight {
+            flashlight.cones = cones;
+        } else if !cones.is_empty() {
+            commands.entity(entity).insert(Flashlight { cones });
+        }
+    }
+}
+
+pub fn reveal_dark_objects(
+    flashlights: Query<&Flashlight>,
+    mut dark_objects: Query<(Entity, &mut Dark, &mut Visibility, &mut CollisionGroups)>,
+    context: Res<RapierContext>,
+) {
+    let mut lit = HashSet::new();
+
+    for flashlight in &flashlights {
+        for cone in &flashlight.cones {
+            for ray in &cone.rays {
+                context.intersections_with_ray(
+                    cone.origin,
+                    ray.direction,
+                    // Also catch lit dark walls that clipped this ray
+                    ray.length + 0.01,
+                    true,
+                    QueryFilter::new().groups(DARK_CG),
+                    |e, _| {
+                        lit.insert(e);
+                        true
+                    },
+                );
+            }
+        }
+    }
+
+    for (entity, mut dark, mut visibility, mut groups) in dark_objects.iter_mut() {
+        let is_lit = lit.contains(&entity);
+        if dark.lit == is_lit {
+            continue;
+        }
+
+        dark.lit = is_lit;
+        if is_lit {
+            *visibility = Visibility::Inherited;
+            *groups = dark.lit_groups;
+        } else {
+            *visibility = Visibility::Hidden;
+            *groups = DARK_CG;
+        }
+    }
+}
+
+fn draw_flashlights(flashlights: Query<&Flashlight>, mut lines: ResMut<DebugLines>) {
+    let color = Color::rgba(1.0, 0.95, 0.6, 0.5);
+    for flashlight in &flashlights {
+        for cone in &flashlight.cones {
+            let origin = cone.origin.extend(1.0);
+            let mut previous: Option<Vec3> = None;
+            for (i, ray) in cone.rays.iter().enumerate() {
+                let end = ray.end(cone.origin).extend(1.0);
+                if i == 0 || i + 1 == cone.rays.len() {
+                    lines.line_colored(origin, end, 0.0, color);
+                }
+                if let Some(previous) = previous {
+                    lines.line_colored(previous, end, 0.0, color);
+                }
+                previous = Some(end);
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::core::objects::monster::Monster;
+use crate::core::objects::player::Player;
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+pub struct GravityFieldPlugin;
+
+impl Plugin for GravityFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (apply_gravity_fields
+                .in_set(GravitySet)
+                .run_if(in_state(GameWorldState::GameWorld)),)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// Systems that update `LocalGravity`, movement systems should run after it.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GravitySet;
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum GravityFieldKind {
+    // Rectangle around the field center, pulls along `direction`
+    Directional { half_size: Vec2, direction: Vec2 },
+    // Circle around the field center, pulls to the center
+    Radial { radius: f32 },
+}
+
+/// Volume that replaces global gravity for players and monsters inside it.
+#[derive(Component, Clone, Debug)]
+pub struct GravityField {
+    pub kind: GravityFieldKind,
+    pub strength: f32,
+}
+
+impl GravityField {
+    /// Gravity at the given point, or `None` if the point is outside of the field.
+    pub fn get_gravity_at(&self, transform: &GlobalTransform, point: Vec2) -> Option<Vec2> {
+        let center = transform.translation().truncate();
+        match self.kind {
+            GravityFieldKind::Directional {
+                half_size,
+                direction,
+            } => {
+                let local = transform
+                    .compute_matrix()
+                    .inverse()
+                    .transform_point3(point.extend(0.0))
+                    .truncate();
+                if local.x.abs() > half_size.x || local.y.abs() > half_size.y {
+                    return None;
+                }
+                let direction = transform
+                    .compute_transform()
+                    .rotation
+                    .mul_vec3(direction.extend(0.0))
+                    .truncate();
+                Some(direction.normalize_or_zero() * self.strength)
+            }
+            GravityFieldKind::Radial { radius } => {
+                let offset = center - point;
+                if offset.length() > radius {
+                    return None;
+                }
+                Some(offset.normalize_or_zero() * self.strength)
+            }
+        }
+    }
+}
+
+/// Direction of gravity for a single body, global gravity unless it is inside some field.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct LocalGravity {
+    pub down: Vec2,
+    pub in_field: bool,
+}
+
+impl Default for LocalGravity {
+    fn default() -> Self {
+        LocalGravity {
+            down: Vec2::NEG_Y,
+            in_field: false,
+        }
+    }
+}
+
+impl LocalGravity {
+    /// Angle between the scene "down" and the local one.
+    pub fn get_angle(&self) -> f32 {
+        Vec2::NEG_Y.angle_between(self.down)
+    }
+}
+
+pub fn apply_gravity_fields(
+    fields: Query<(&GravityField, &GlobalTransform)>,
+    mut bodies: Query<
+        (
+            &Transform,
+            &ReadMassProperties,
+            &mut LocalGravity,
+            &mut GravityScale,
+            &mut ExternalImpulse,
+            Option<&Monster>,
+        ),
+        Or<(With<Player>, With<Monster>)>,
+    >,
+    config: Res<RapierConfiguration>,
+    time: Res<FixedTime>,
+) {
+    for (transform, mass, mut local_gravity, mut gravity_scale, mut impulse, monster) in
+        bodies.iter_mut()
+    {
+        if monster.is_some_and(|monster| monster.kind.ignores_gravity()) {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+
+        // Overlapping fields add up
+        let gravity = fields
+            .iter()
+            .filter_map(|(field, field_transform)| field.get_gravity_at(field_transform, position))
+            .reduce(|a, b| a + b);
+
+        if let Some(gravity) = gravity {
+            // Global gravity is replaced, not added
+            gravity_scale.0 = 0.0;
+            impulse.impulse += gravity * mass.0.mass * time.period.as_secs_f32();
+            local_gravity.in_field = true;
+            if gravity.length() > 0.01 {
+                local_gravity.down = gravity.normalize();
+            }
+        } else {
+            gravity_scale.0 = 1.0;
+            local_gravity.in_field = false;
+            if config.gravity.length() > 0.01 {
+                local_gravity.down = config.gravity.normalize();
+            }
+        }
+    }
+}
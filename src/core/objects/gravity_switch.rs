@@ -0,0 +1,93 @@
+use crate::core::deterministic::FixedEventApp;
+use crate::core::direction::SceneDirection;
+use crate::core::objects::player::Player;
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+pub struct GravitySwitchPlugin;
+
+impl Plugin for GravitySwitchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_fixed_event::<GravityChanged>();
+        app.add_systems(
+            (gravity_switch_trigger.run_if(in_state(GameWorldState::GameWorld)),)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GravityChange {
+    Set(SceneDirection),
+    RotateCW,
+    RotateCCW,
+    Flip,
+}
+
+impl GravityChange {
+    pub fn apply(&self, direction: SceneDirection) -> SceneDirection {
+        match *self {
+            GravityChange::Set(direction) => direction,
+            GravityChange::RotateCW => direction.get_perp().get_opposite(),
+            GravityChange::RotateCCW => direction.get_perp(),
+            GravityChange::Flip => direction.get_opposite(),
+        }
+    }
+}
+
+/// Trigger that changes gravity when some player touches it.
+#[derive(Component, Clone, Debug)]
+pub struct GravitySwitch {
+    pub change: GravityChange,
+}
+
+/// Global gravity direction was changed by a switch.
+#[derive(Clone, Debug)]
+pub struct GravityChanged {
+    pub from: SceneDirection,
+    pub to: SceneDirection,
+    pub switch: Entity,
+    pub player: Entity,
+}
+
+fn gravity_switch_trigger(
+    mut collision_events: EventReader<CollisionEvent>,
+    players: Query<(), With<Player>>,
+    switches: Query<&GravitySwitch>,
+    mut config: ResMut<RapierConfiguration>,
+    mut changed_events: EventWriter<GravityChanged>,
+) {
+    for collision_event in collision_events.iter() {
+        if let CollisionEvent::Started(a, b, _args) = collision_event {
+            let (player, switch) = if players.contains(*a) {
+                (*a, *b)
+            } else if players.contains(*b) {
+                (*b, *a)
+            } else {
+                continue;
+            };
+
+            let Ok(gravity_switch) = switches.get(switch) else {
+                continue;
+            };
+
+            let from = SceneDirection::from_gravity_direction(&config);
+            let to = gravity_switch.change.apply(from);
+            if from == to {
+                continue;
+            }
+
+            let strength = config.gravity.length().max(9.8);
+            config.gravity = to.get_vec() * strength;
+
+            changed_events.send(GravityChanged {
+                from,
+                to,
+                switch,
+                player,
+            });
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::core::objects::health::{
+    player_contacts, Health, PlayerColliders, PlayerDamaged, PlayerDied, ShieldBlocked,
+};
+use crate::core::objects::player::Player;
+use crate::core::objects::respawn::Respawning;
+use crate::core::objects::side_effect::SideEffect;
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+pub struct HazardPlugin;
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                hazard_contact_damage.run_if(in_state(GameWorldState::GameWorld)),
+                lava_kill.run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HazardKind {
+    // Damage and push the player away
+    Spikes,
+    // Kill the player at once
+    Lava,
+}
+
+/// Hurts players: solid spikes when touched with a side without `SideEffect::Shield`,
+/// sensor lava as soon as the player enters it.
+#[derive(Component, Clone, Debug)]
+pub struct Hazard {
+    pub kind: HazardKind,
+    pub damage: f32,
+}
+
+impl Hazard {
+    pub const SPIKES_KNOCKBACK: f32 = 5.0;
+}
+
+fn hazard_contact_damage(
+    hazards: Query<(Entity, &Hazard)>,
+    mut players: Query<
+        (
+            &Transform,
+            &Player,
+            &mut Health,
+            &mut ExternalImpulse,
+            &ReadMassProperties,
+        ),
+        Without<Respawning>,
+    >,
+    player_colliders: PlayerColliders,
+    context: Res<RapierContext>,
+    mut damaged_events: EventWriter<PlayerDamaged>,
+    mut died_events: EventWriter<PlayerDied>,
+    mut blocked_events: EventWriter<ShieldBlocked>,
+) {
+    for (hazard_entity, hazard) in &hazards {
+        if hazard.kind != HazardKind::Spikes {
+            continue;
+        }
+
+        for (player_entity, normal) in player_contacts(&context, hazard_entity, &player_colliders) {
+            let Ok((transform, player, mut health, mut impulse, mass)) =
+                players.get_mut(player_entity)
+            else {
+                continue;
+            };
+
+            let side = player.get_side_facing(transform, normal);
+
+            if player.effects[side] == SideEffect::Shield {
+                if !health.is_invulnerable() {
+                    blocked_events.send(ShieldBlocked {
+                        player: player_entity,
+                        source: hazard_entity,
+                        side,
+                    });
+                }
+                continue;
+            }
+
+            let amount = hazard.damage;
+            if health.damage(amount) {
+                damaged_events.send(PlayerDamaged {
+                    player: player_entity,
+                    source: hazard_entity,
+                    amount,
+                    health_left: health.current,
+                });
+
+                if health.is_dead() {
+                    died_events.send(PlayerDied {
+                        player: player_entity,
+                    });
+                } else {
+                    impulse.impulse += -normal * Hazard::SPIKES_KNOCKBACK * mass.0.mass;
+                }
+            }
+        }
+    }
+}
+
+fn lava_kill(
+    hazards: Query<(Entity, &Hazard)>,
+    mut players: Query<&mut Health, (With<Player>, Without<Respawning>)>,
+    player_colliders: PlayerColliders,
+    context: Res<RapierContext>,
+    mut died_events: EventWriter<PlayerDied>,
+) {
+    for (hazard_entity, hazard) in &hazards {
+        if hazard.kind != HazardKind::Lava {
+            continue;
+        }
+
+        for (collider1, collider2, intersecting) in context.intersections_with(hazard_entity) {
+            if !intersecting {
+                continue;
+            }
+
+            let other = if collider1 == hazard_entity {
+                collider2
+            } else {
+                collider1
+            };
+
+            let Some(player_entity) = player_colliders.get_player(other) else {
+                continue;
+            };
+            let Ok(mut health) = players.get_mut(player_entity) else {
+                continue;
+            };
+
+            // Lava ignores shields and invulnerability, same as falling out of the scene
+            if !health.is_dead() {
+                health.current = 0.0;
+                died_events.send(PlayerDied {
+                    player: player_entity,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::objects::shape::{PlayerShape, MAX_SIDES};
+
+    // Player falls onto the hazard that lies on the floor
+    fn app_above_hazard(kind: HazardKind) -> HeadlessApp {
+        let mut app = HeadlessApp::new();
+        app.build_scene(|builder| {
+            builder.spawn_wall_from_to(Vec2::new(-5.0, -2.0), Vec2::new(5.0, -3.0));
+            builder.spawn_hazard_from_to(Vec2::new(-5.0, -1.0), Vec2::new(5.0, -2.0), kind);
+            builder.spawn_player(Vec2::new(0.0, 0.0), 0, PlayerShape::Square);
+        });
+        app
+    }
+
+    fn get_player_health(app: &mut HeadlessApp) -> Health {
+        let entity = app.get_player_entity(0);
+        app.app.world.get::<Health>(entity).unwrap().clone()
+    }
+
+    #[test]
+    fn spikes_damage_player() {
+        let mut app = app_above_hazard(HazardKind::Spikes);
+        let damaged = app.run_counting::<PlayerDamaged>(SECOND / 2);
+
+        // Invulnerability after the hit lasts longer than the test
+        let health = get_player_health(&mut app);
+        assert_eq!(damaged, 1);
+        assert_eq!(health.current, health.max - 1.0);
+    }
+
+    #[test]
+    fn shielded_side_blocks_spikes() {
+        let mut app = app_above_hazard(HazardKind::Spikes);
+        for side in 0..MAX_SIDES {
+            app.set_player_effect(0, side, SideEffect::Shield);
+        }
+        let blocked = app.run_counting::<ShieldBlocked>(SECOND);
+
+        let health = get_player_health(&mut app);
+        assert!(blocked > 0);
+        assert_eq!(health.current, health.max);
+    }
+
+    #[test]
+    fn lava_kills_player() {
+        let mut app = app_above_hazard(HazardKind::Lava);
+        let died = app.run_counting::<PlayerDied>(SECOND);
+
+        let entity = app.get_player_entity(0);
+        assert_eq!(died, 1);
+        assert!(app.app.world.get::<Respawning>(entity).is_some());
+    }
+}
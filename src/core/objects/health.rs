@@ -0,0 +1,271 @@
+use crate::core::deterministic::FixedEventApp;
+use crate::core::objects::monster::{Monster, MonsterDamaged, MonsterDying};
+use crate::core::objects::player::Player;
+use crate::core::objects::side_effect::SideEffect;
+use crate::states::GameWorldState;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_fixed_event::<PlayerDamaged>();
+        app.add_fixed_event::<PlayerDied>();
+        app.add_fixed_event::<ShieldBlocked>();
+
+        app.add_systems(
+            (
+                advance_health.run_if(in_state(GameWorldState::GameWorld)),
+                monster_contact_damage
+                    .after(advance_health)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+    // Time after taking damage during which new damage is ignored
+    pub invulnerability: f32,
+    pub time_since_damaged: f32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Health::new(3.0)
+    }
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Health {
+            current: max,
+            max,
+            invulnerability: 1.0,
+            time_since_damaged: f32::INFINITY,
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.time_since_damaged < self.invulnerability
+    }
+
+    /// Returns true if damage was actually applied.
+    pub fn damage(&mut self, amount: f32) -> bool {
+        if self.is_dead() || self.is_invulnerable() {
+            return false;
+        }
+        self.current = (self.current - amount).max(0.0);
+        self.time_since_damaged = 0.0;
+        true
+    }
+
+    pub fn restore(&mut self) {
+        self.current = self.max;
+        self.time_since_damaged = f32::INFINITY;
+    }
+}
+
+/// Player lost some health.
+#[derive(Clone, Debug)]
+pub struct PlayerDamaged {
+    pub player: Entity,
+    pub source: Entity,
+    pub amount: f32,
+    pub health_left: f32,
+}
+
+/// Player health dropped to zero.
+#[derive(Clone, Debug)]
+pub struct PlayerDied {
+    pub player: Entity,
+}
+
+/// Hit was absorbed by a side with `SideEffect::Shield`.
+#[derive(Clone, Debug)]
+pub struct ShieldBlocked {
+    pub player: Entity,
+    pub source: Entity,
+    pub side: usize,
+}
+
+/// Colliders of players: player bodies and their sides.
+#[derive(SystemParam)]
+pub struct PlayerColliders<'w, 's> {
+    players: Query<'w, 's, (), With<Player>>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+
+impl PlayerColliders<'_, '_> {
+    /// Player that owns the collider.
+    pub fn get_player(&self, collider: Entity) -> Option<Entity> {
+        if self.players.contains(collider) {
+            return Some(collider);
+        }
+
+        let parent = self.parents.get(collider).ok()?.get();
+        self.players.contains(parent).then_some(parent)
+    }
+}
+
+/// Players touching the solid collider of `entity`, with the contact normal pointing from the
+/// player to `entity`. A player touching it with several colliders is returned for each of them.
+pub fn player_contacts<'a>(
+    context: &'a RapierContext,
+    entity: Entity,
+    players: &'a PlayerColliders<'a, 'a>,
+) -> impl Iterator<Item = (Entity, Vec2)> + 'a {
+    context
+        .contacts_with(entity)
+        .filter(|pair| pair.has_any_active_contacts())
+        .filter_map(move |pair| {
+            let (other, sign) = if pair.collider1() == entity {
+                (pair.collider2(), -1.0)
+            } else {
+                (pair.collider1(), 1.0)
+            };
+            let player = players.get_player(other)?;
+            let (manifold, _) = pair.find_deepest_contact()?;
+
+            // Manifold normal points out of collider1
+            Some((player, manifold.normal() * sign))
+        })
+}
+
+fn advance_health(mut healths: Query<&mut Health>, time: Res<FixedTime>) {
+    for mut health in healths.iter_mut() {
+        if health.time_since_damaged.is_finite() {
+            health.time_since_damaged += time.period.as_secs_f32();
+        }
+    }
+}
+
+pub fn monster_contact_damage(
+    mut players: Query<(&Transform, &Player, &mut Health)>,
+    player_colliders: PlayerColliders,
+    mut monsters: Query<
+        (
+            Entity,
+            &mut ExternalImpulse,
+            &Velocity,
+            &ReadMassProperties,
+            &mut Health,
+        ),
+        (With<Monster>, Without<Player>, Without<MonsterDying>),
+    >,
+    context: Res<RapierContext>,
+    mut damaged_events: EventWriter<PlayerDamaged>,
+    mut died_events: EventWriter<PlayerDied>,
+    mut blocked_events: EventWriter<ShieldBlocked>,
+    mut monster_damaged_events: EventWriter<MonsterDamaged>,
+) {
+    for (monster_entity, mut impulse, velocity, mass, mut monster_health) in monsters.iter_mut() {
+        for (player_entity, normal) in player_contacts(&context, monster_entity, &player_colliders)
+        {
+            let Ok((transform, player, mut health)) = players.get_mut(player_entity) else {
+                continue;
+            };
+
+            let side = player.get_side_facing(transform, normal);
+
+            if player.effects[side] == SideEffect::Thorns {
+                let amount = 1.0;
+                if monster_health.damage(amount) {
+                    monster_damaged_events.send(MonsterDamaged {
+                        monster: monster_entity,
+                        source: player_entity,
+                        amount,
+                    });
+                    impulse.impulse += normal * 3.0 * mass.0.mass;
+                }
+                continue;
+            }
+
+            if player.effects[side] == SideEffect::Shield {
+                let knockback_speed = 4.0;
+                let away_speed = velocity.linvel.dot(normal);
+                if away_speed < knockback_speed * 0.5 {
+                    impulse.impulse += normal * (knockback_speed - away_speed) * mass.0.mass;
+                    blocked_events.send(ShieldBlocked {
+                        player: player_entity,
+                        source: monster_entity,
+                        side,
+                    });
+                }
+                continue;
+            }
+
+            let amount = 1.0;
+            if health.damage(amount) {
+                damaged_events.send(PlayerDamaged {
+                    player: player_entity,
+                    source: monster_entity,
+                    amount,
+                    health_left: health.current,
+                });
+                if health.is_dead() {
+                    died_events.send(PlayerDied {
+                        player: player_entity,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::objects::monster::MonsterKind;
+    use crate::core::objects::shape::PlayerShape;
+
+    // Walker chases the player from the right, `side` is the player side looking at it
+    fn app_with_monster_on_right(side: usize, effect: SideEffect) -> HeadlessApp {
+        let mut app = HeadlessApp::new();
+        app.build_scene(|builder| {
+            builder.spawn_wall_from_to(Vec2::new(-10.0, -1.0), Vec2::new(10.0, -2.0));
+            builder.spawn_player(Vec2::new(0.0, -0.5), 0, PlayerShape::Square);
+            builder.spawn_monster(Vec2::new(2.0, -0.5), MonsterKind::Walker, false);
+        });
+        app.set_player_effect(0, side, effect);
+        app
+    }
+
+    fn get_player_health(app: &mut HeadlessApp) -> Health {
+        let entity = app.get_player_entity(0);
+        app.app.world.get::<Health>(entity).unwrap().clone()
+    }
+
+    #[test]
+    fn shield_facing_monster_blocks_it() {
+        // Side 1 of the square looks right
+        let mut app = app_with_monster_on_right(1, SideEffect::Shield);
+        let blocked = app.run_counting::<ShieldBlocked>(2 * SECOND);
+
+        let health = get_player_health(&mut app);
+        assert!(blocked > 0);
+        assert_eq!(health.current, health.max);
+    }
+
+    #[test]
+    fn shield_facing_away_does_not_block_monster() {
+        // Side 3 of the square looks left
+        let mut app = app_with_monster_on_right(3, SideEffect::Shield);
+        let blocked = app.run_counting::<ShieldBlocked>(2 * SECOND);
+
+        let health = get_player_health(&mut app);
+        assert_eq!(blocked, 0);
+        assert!(health.current < health.max);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::core::deterministic::FixedEventApp;
+use crate::core::objects::collision_groups::LASER_CG;
+use crate::core::objects::door::DoorChannels;
+use crate::core::objects::health::Health;
+use crate::core::objects::monster::{Monster, MonsterDamaged, MonsterDying};
+use crate::core::objects::player::Player;
+use crate::core::objects::side_effect::SideEffect;
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+
+pub struct LaserPlugin;
+
+impl Plugin for LaserPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_fixed_event::<LaserHit>();
+
+        app.add_systems(
+            (
+                fire_lasers.run_if(in_state(GameWorldState::GameWorld)),
+                damage_monsters_with_laser
+                    .after(fire_lasers)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+                update_laser_receivers
+                    .after(fire_lasers)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+
+        app.add_system(
+            draw_lasers
+                .run_if(resource_exists::<DebugLines>())
+                .run_if(in_state(GameWorldState::GameWorld)),
+        );
+    }
+}
+
+/// Beams of all player sides with `SideEffect::Laser`.
+#[derive(Component, Clone, Debug, Default)]
+pub struct Laser {
+    pub beams: Vec<LaserBeam>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LaserBeam {
+    pub side: usize,
+    pub from: Vec2,
+    pub to: Vec2,
+    pub hit: Option<Entity>,
+}
+
+impl LaserBeam {
+    pub const RANGE: f32 = 20.0;
+}
+
+/// Laser beam touched some collider this frame.
+#[derive(Clone, Debug)]
+pub struct LaserHit {
+    pub player: Entity,
+    pub side: usize,
+    pub target: Entity,
+    pub point: Vec2,
+    pub normal: Vec2,
+}
+
+/// Switch that opens doors of its channel while a laser beam hits it.
+#[derive(Component, Clone, Debug)]
+pub struct LaserReceiver {
+    pub channel: u32,
+    pub time_since_hit: f32,
+}
+
+impl LaserReceiver {
+    // Stay active for a moment, so a shaking beam does not blink the doors
+    pub const HOLD_TIME: f32 = 0.1;
+
+    pub fn new(channel: u32) -> Self {
+        LaserReceiver {
+            channel,
+            time_since_hit: f32::INFINITY,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.time_since_hit < Self::HOLD_TIME
+    }
+
+    pub fn get_color(active: bool) -> Color {
+        if active {
+            Color::rgb(4.0, 0.3, 0.3)
+        } else {
+            Color::rgb(0.4, 0.1, 0.1)
+        }
+    }
+}
+
+fn fire_lasers(
+    mut commands: Commands,
+    mut players: Query<(Entity, &Player, &Transform, Option<&mut Laser>)>,
+    context: Res<RapierContext>,
+    mut hit_events: EventWriter<LaserHit>,
+) {
+    for (entity, player, transform, laser) in players.iter_mut() {
+        let mut beams = vec![];
+
+        let centers = player.get_side_centers();
+        let directions = player.get_side_directions();
+
+        for i in 0..centers.len() {
+            if player.effects[i] != SideEffect::Laser {
+                continue;
+            }
+
+            let from = transform.transform_point(centers[i].extend(0.0)).truncate();
+            let direction = (transform.rotation * directions[i].extend(0.0))
+                .truncate()
+                .normalize();
+
+            let hit = context.cast_ray_and_get_normal(
+                from,
+                direction,
+                LaserBeam::RANGE,
+                true,
+                QueryFilter::new().groups(LASER_CG).exclude_sensors(),
+            );
+
+            let mut beam = LaserBeam {
+                side: i,
+                from,
+                to: from + direction * LaserBeam::RANGE,
+                hit: None,
+            };
+
+            if let Some((target, intersection)) = hit {
+                beam.to = intersection.point;
+                beam.hit = Some(target);
+                hit_events.send(LaserHit {
+                    player: entity,
+                    side: i,
+                    target,
+                    point: intersection.point,
+                    normal: intersection.normal,
+                });
+            }
+
+            beams.push(beam);
+        }
+
+        if let Some(mut laser) = laser {
+            laser.beams = beams;
+        } else if !beams.is_empty() {
+            commands.entity(entity).insert(Laser { beams });
+        }
+    }
+}
+
+fn damage_monsters_with_laser(
+    mut hit_events: EventReader<LaserHit>,
+    mut monsters: Query<&mut Health, (With<Monster>, Without<MonsterDying>)>,
+    mut damaged_events: EventWriter<MonsterDamaged>,
+) {
+    for hit in hit_events.iter() {
+        if let Ok(mut health) = monsters.get_mut(hit.target) {
+            let amount = 1.0;
+            if health.damage(amount) {
+                damaged_events.send(MonsterDamaged {
+                    monster: hit.target,
+                    source: hit.player,
+                    amount,
+                });
+            }
+        }
+    }
+}
+
+fn update_laser_receivers(
+    mut hit_events: EventReader<LaserHit>,
+    mut receivers: Query<(Entity, &mut LaserReceiver, &Handle<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut channels: ResMut<DoorChannels>,
+    time: Res<FixedTime>,
+) {
+    let hits: Vec<Entity> = hit_events.iter().map(|hit| hit.target).collect();
+
+    let mut active_channels = HashMap::new();
+
+    for (entity, mut receiver, handle) in receivers.iter_mut() {
+        let was_active = receiver.is_active();
+
+        if hits.contains(&entity) {
+            receiver.time_since_hit = 0.0;
+        } else {
+            receiver.time_since_hit += time.period.as_secs_f32();
+        }
+
+        let active = receiver.is_active();
+        *active_channels.entry(receiver.channel).or_insert(false) |= active;
+
+        if was_active != active {
+            if let Some(material) = materials.get_mut(handle) {
+                material.color = LaserReceiver::get_color(active);
+            }
+        }
+    }
+
+    for (channel, active) in active_channels {
+        channels.set(channel, active);
+    }
+}
+
+fn draw_lasers(lasers: Query<&Laser>, mut lines: ResMut<DebugLines>) {
+    for laser in &lasers {
+        for beam in &laser.beams {
+            lines.line_colored(
+                beam.from.extend(1.0),
+                beam.to.extend(1.0),
+                0.0,
+                Color::rgb(4.0, 0.2, 0.2),
+            );
+        }
+    }
+}
@@ -1,6 +1,20 @@
 pub mod bonus;
 pub mod collision_groups;
+pub mod door;
+pub mod effect_thief;
+pub mod flashlight;
+pub mod gravity_field;
+pub mod gravity_switch;
+pub mod hazard;
+pub mod health;
+pub mod laser;
 pub mod monster;
+pub mod monster_ai;
+pub mod navigation;
+pub mod one_way_platform;
+pub mod platform;
 pub mod player;
+pub mod respawn;
 pub mod shape;
 pub mod side_effect;
+pub mod spawner;
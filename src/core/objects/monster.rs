@@ -1,83 +1,515 @@
-use crate::core::direction::SceneDirection;
-use crate::core::materials::monster_material::MonsterMaterial;
+use crate::core::deterministic::{FixedEventApp, GameRng};
+use crate::core::materials::monster_material::{MonsterMaterial, MonsterStateFlags};
+use crate::core::objects::collision_groups::{
+    MONSTER_BIT, PATROL_GROUND_CG, PATROL_WALL_CG, WALL_BIT,
+};
+use crate::core::objects::gravity_field::{GravitySet, LocalGravity};
+use crate::core::objects::health::Health;
 use crate::states::GameWorldState;
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
 
-use super::player::Player;
+use crate::core::objects::monster_ai::{is_target_reached, MonsterAi, MonsterState};
+use crate::core::objects::navigation::{MonsterPath, NavLinkKind};
+use crate::core::objects::side_effect::SideEffect;
 
 #[derive(Component, Debug, Clone, Default)]
 pub struct Monster {
+    pub kind: MonsterKind,
     pub patrol: bool,
+    pub patrol_bounds: Option<PatrolBounds>,
+    // Left and right are relative to the local gravity
+    pub facing_left: bool,
+    // Time left until the jumper may jump again
+    pub jump_cooldown: f32,
+    // Side effect a thief took from a player, it is dropped as a bonus on death
+    pub stolen_effect: Option<SideEffect>,
 }
 
-impl Monster {}
+impl Monster {
+    // How far ahead of its body the patrolling monster looks for walls and ledges
+    pub const LOOK_AHEAD: f32 = 0.1;
+    // Drops deeper than this are ledges, smaller ones are just steps down
+    pub const MAX_STEP_DOWN: f32 = 0.4;
+    pub const JUMP_INTERVAL: f32 = 1.5;
+}
+
+/// Archetype of the monster, it selects the collider, stats, behaviour and the row of the sprite.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MonsterKind {
+    #[default]
+    Walker,
+    // Walks like a walker, but jumps from time to time
+    Jumper,
+    // Sticks to walls and ceilings and follows them around corners
+    Crawler,
+    // Ignores gravity and flies straight to the target
+    Flyer,
+    // Slow, heavy and tough, pushes players around
+    Pusher,
+    // Takes side effects from player sides it touches
+    Thief,
+}
+
+impl MonsterKind {
+    pub fn get_index(&self) -> u32 {
+        match self {
+            MonsterKind::Walker => 0,
+            MonsterKind::Jumper => 1,
+            MonsterKind::Crawler => 2,
+            MonsterKind::Flyer => 3,
+            MonsterKind::Pusher => 4,
+            MonsterKind::Thief => 5,
+        }
+    }
+
+    pub fn get_speed(&self) -> f32 {
+        match self {
+            MonsterKind::Walker | MonsterKind::Jumper => 2.0,
+            MonsterKind::Crawler => 1.5,
+            MonsterKind::Flyer | MonsterKind::Thief => 2.5,
+            MonsterKind::Pusher => 1.0,
+        }
+    }
+
+    pub fn get_acceleration(&self) -> f32 {
+        match self {
+            MonsterKind::Pusher => 4.0,
+            _ => 12.0,
+        }
+    }
+
+    pub fn get_half_size(&self) -> f32 {
+        match self {
+            MonsterKind::Crawler => 0.35,
+            MonsterKind::Pusher => 0.7,
+            MonsterKind::Thief => 0.4,
+            _ => 0.5,
+        }
+    }
+
+    /// Only jumpers can jump, navigation uses it to find out how high they get.
+    pub fn get_jump_speed(&self) -> f32 {
+        match self {
+            MonsterKind::Jumper => 6.0,
+            _ => 0.0,
+        }
+    }
+
+    pub fn get_max_health(&self) -> f32 {
+        match self {
+            MonsterKind::Pusher => 3.0,
+            _ => 1.0,
+        }
+    }
+
+    pub fn get_density(&self) -> f32 {
+        match self {
+            MonsterKind::Pusher => 4.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Global gravity and gravity fields do not affect these kinds.
+    pub fn ignores_gravity(&self) -> bool {
+        matches!(self, MonsterKind::Crawler | MonsterKind::Flyer)
+    }
+}
+
+/// Segment the patrolling monster walks along, only its projection on the ground matters.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct PatrolBounds {
+    pub from: Vec2,
+    pub to: Vec2,
+}
+
+/// Monster is playing its death animation and will be despawned after it.
+#[derive(Component, Debug, Clone, Default)]
+pub struct MonsterDying {
+    pub time: f32,
+}
+
+impl MonsterDying {
+    pub const DURATION: f32 = 0.6;
+}
+
+/// Monster lost some health.
+#[derive(Clone, Debug)]
+pub struct MonsterDamaged {
+    pub monster: Entity,
+    pub source: Entity,
+    pub amount: f32,
+}
+
+/// Monster health dropped to zero, it starts dying.
+#[derive(Clone, Debug)]
+pub struct MonsterKilled {
+    pub monster: Entity,
+}
 
 pub struct MonsterPlugin;
 
 impl Plugin for MonsterPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems((move_monster.run_if(in_state(GameWorldState::GameWorld)),));
+        app.add_fixed_event::<MonsterDamaged>();
+        app.add_fixed_event::<MonsterKilled>();
+
+        app.add_systems(
+            (
+                crawl_surfaces
+                    .after(GravitySet)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+                face_new_monsters.run_if(in_state(GameWorldState::GameWorld)),
+                move_monster
+                    .after(crawl_surfaces)
+                    .after(face_new_monsters)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+                kill_monsters.run_if(in_state(GameWorldState::GameWorld)),
+                animate_dying_monsters.run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
     }
 }
 
 pub fn move_monster(
-    mut monsters: Query<(
-        &mut Monster,
-        &Transform,
-        &mut ExternalImpulse,
-        &Velocity,
-        &ReadMassProperties,
-        &Handle<MonsterMaterial>,
-    )>,
-    players: Query<&Transform, With<Player>>,
-    config: ResMut<RapierConfiguration>,
-    time: Res<Time>,
+    mut monsters: Query<
+        (
+            Entity,
+            &mut Monster,
+            &Transform,
+            &mut ExternalImpulse,
+            &Velocity,
+            &ReadMassProperties,
+            &Health,
+            &LocalGravity,
+            &Handle<MonsterMaterial>,
+            &MonsterAi,
+            &MonsterPath,
+        ),
+        Without<MonsterDying>,
+    >,
+    time: Res<FixedTime>,
     mut materials: ResMut<Assets<MonsterMaterial>>,
+    context: Res<RapierContext>,
 ) {
-    if players.is_empty() {
-        return;
-    }
-
-    let nearest_player = players.single();
-    let gravity_direction = SceneDirection::from_gravity_direction(&config);
-    let right = gravity_direction.get_vec().perp();
+    for (
+        entity,
+        mut monster,
+        transform,
+        mut impulse,
+        velocity,
+        mass,
+        health,
+        gravity,
+        handle,
+        ai,
+        path,
+    ) in monsters.iter_mut()
+    {
+        let position = transform.translation.truncate();
+        let right = gravity.down.perp();
+        let speed = monster.kind.get_speed();
+        let limit = monster.kind.get_acceleration() * time.period.as_secs_f32();
 
-    for (monster, transform, mut impulse, velocity, mass, handle) in monsters.iter_mut() {
-        let mut state = 0;
+        // Path leads around walls and over gaps, without it the monster steers straight
+        let next_waypoint = path.waypoints.front();
+        let target = next_waypoint
+            .map(|waypoint| waypoint.position)
+            .or(ai.get_target())
+            .filter(|target| !is_target_reached(monster.kind, position, *target, gravity.down));
 
-        if velocity.linvel.x < 0.0 {
-            state |= 1;
+        // Alert monster does not move yet, but already looks at the player
+        if let Some(look_at) = target.or(ai.last_seen.filter(|_| ai.state == MonsterState::Alert)) {
+            let offset = (look_at - position).dot(right);
+            if offset.abs() > MonsterAi::REACH_DISTANCE {
+                monster.facing_left = offset < 0.0;
+            }
         }
 
-        {
+        let patrolling = ai.state == MonsterState::Patrol;
+
+        if monster.kind == MonsterKind::Flyer {
+            let mut target_velocity = Vec2::ZERO;
+            if patrolling {
+                if should_turn(entity, &monster, transform, gravity.down, &context) {
+                    monster.facing_left = !monster.facing_left;
+                }
+                target_velocity = if monster.facing_left { -right } else { right } * speed;
+            } else if let Some(target) = target {
+                target_velocity = (target - position).normalize_or_zero() * speed;
+            }
+
+            let delta_velocity = (target_velocity - velocity.linvel).clamp_length_max(limit);
+
+            impulse.impulse += delta_velocity * mass.0.mass;
+        } else {
+            let velocity_right = velocity.linvel.dot(right);
+
             let mut target_velocity = 0.0;
-            if monster.patrol {
-                if velocity.linvel.x > 0.05 || (velocity.linvel.x > -0.05 && rand::random::<bool>())
+            if patrolling {
+                // Crawlers never turn, they follow the surface around corners instead
+                if monster.kind != MonsterKind::Crawler
+                    && should_turn(entity, &monster, transform, gravity.down, &context)
                 {
-                    target_velocity = 2.0;
-                } else {
-                    target_velocity = -2.0;
-                }
-            } else {
-                if transform.translation.x > nearest_player.translation.x {
-                    target_velocity -= 2.0;
-                } else {
-                    target_velocity += 2.0;
+                    monster.facing_left = !monster.facing_left;
                 }
+                target_velocity = if monster.facing_left { -speed } else { speed };
+            } else if target.is_some() {
+                target_velocity = if monster.facing_left { -speed } else { speed };
             }
 
-            let mut delta_velocity = target_velocity - velocity.linvel.dot(right);
+            let delta_velocity = (target_velocity - velocity_right).clamp(-limit, limit);
 
-            let limit = 12.0 * time.delta_seconds();
+            impulse.impulse += right * delta_velocity * mass.0.mass;
+        }
 
-            delta_velocity = delta_velocity.clamp(-limit, limit);
+        monster.jump_cooldown -= time.period.as_secs_f32();
 
-            impulse.impulse += right * delta_velocity * mass.0.mass;
+        let wants_jump = match next_waypoint {
+            Some(waypoint) => waypoint.link == NavLinkKind::Jump,
+            // Without a path jumpers just jump from time to time
+            None => patrolling || target.is_some(),
+        };
+
+        let jump_speed = monster.kind.get_jump_speed();
+        if jump_speed > 0.0 && wants_jump {
+            let reach = monster.kind.get_half_size() + Monster::LOOK_AHEAD;
+            if monster.jump_cooldown <= 0.0
+                && cast_ray(
+                    entity,
+                    position,
+                    gravity.down,
+                    reach,
+                    PATROL_GROUND_CG,
+                    &context,
+                )
+            {
+                monster.jump_cooldown = Monster::JUMP_INTERVAL;
+                let up_velocity = -velocity.linvel.dot(gravity.down);
+                impulse.impulse +=
+                    -gravity.down * (jump_speed - up_velocity).max(0.0) * mass.0.mass;
+            }
+        }
+
+        let mut state = MonsterStateFlags::NONE;
+
+        if monster.facing_left {
+            state |= MonsterStateFlags::FACING_LEFT;
+        }
+
+        if health.is_invulnerable() {
+            state |= MonsterStateFlags::HURT;
+        }
+
+        if let Some(m) = materials.get_mut(handle) {
+            m.state = state.with_ai_state(ai.state.get_index())
+                | MonsterStateFlags::get_stolen_effect_bits(monster.stolen_effect);
+        }
+    }
+}
+
+/// New monsters start walking to a random side, so monsters of one spawner do not march in line.
+fn face_new_monsters(mut monsters: Query<&mut Monster, Added<Monster>>, mut rng: ResMut<GameRng>) {
+    for mut monster in monsters.iter_mut() {
+        monster.facing_left = rng.0.gen();
+    }
+}
+
+/// Crawlers have their own gravity that pulls them to the surface they walk on.
+fn crawl_surfaces(
+    mut monsters: Query<
+        (
+            Entity,
+            &Monster,
+            &mut Transform,
+            &mut LocalGravity,
+            &mut ExternalImpulse,
+            &ReadMassProperties,
+        ),
+        Without<MonsterDying>,
+    >,
+    config: Res<RapierConfiguration>,
+    context: Res<RapierContext>,
+    time: Res<FixedTime>,
+) {
+    for (entity, monster, mut transform, mut gravity, mut impulse, mass) in monsters.iter_mut() {
+        if monster.kind != MonsterKind::Crawler {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let down = gravity.down;
+        let forward = if monster.facing_left {
+            -down.perp()
+        } else {
+            down.perp()
+        };
+        let reach = monster.kind.get_half_size() + Monster::LOOK_AHEAD;
+
+        let filter = QueryFilter::new()
+            .groups(PATROL_GROUND_CG)
+            .exclude_rigid_body(entity)
+            .exclude_sensors();
+        let surface = context
+            .cast_ray_and_get_normal(position, down, reach, true, filter)
+            .map(|(_, intersection)| intersection.normal);
+
+        if cast_ray(entity, position, forward, reach, PATROL_WALL_CG, &context) {
+            // Climb the wall ahead
+            gravity.down = forward;
+        } else if let Some(normal) = surface {
+            let ground_ahead = cast_ray(
+                entity,
+                position + forward * reach,
+                down,
+                reach + Monster::MAX_STEP_DOWN,
+                PATROL_GROUND_CG,
+                &context,
+            );
+            gravity.down = if ground_ahead {
+                -normal
+            } else {
+                // Go around the corner of the ledge
+                -forward
+            };
+        }
+
+        transform.rotation = Quat::from_rotation_z(Vec2::NEG_Y.angle_between(gravity.down));
+
+        impulse.impulse +=
+            gravity.down * config.gravity.length() * mass.0.mass * time.period.as_secs_f32();
+    }
+}
+
+/// Patrolling monster turns around at walls, ledges and the ends of its patrol bounds.
+fn should_turn(
+    entity: Entity,
+    monster: &Monster,
+    transform: &Transform,
+    down: Vec2,
+    context: &RapierContext,
+) -> bool {
+    let position = transform.translation.truncate();
+    let forward = if monster.facing_left {
+        -down.perp()
+    } else {
+        down.perp()
+    };
+
+    if let Some(bounds) = monster.patrol_bounds {
+        let from = bounds.from.dot(forward);
+        let to = bounds.to.dot(forward);
+        if position.dot(forward) >= from.max(to) {
+            return true;
+        }
+    }
+
+    let reach = monster.kind.get_half_size() + Monster::LOOK_AHEAD;
+
+    if cast_ray(entity, position, forward, reach, PATROL_WALL_CG, context) {
+        return true;
+    }
+
+    // Ledges matter only while standing on the ground, falling monsters keep their direction
+    let grounded = cast_ray(entity, position, down, reach, PATROL_GROUND_CG, context);
+    let ground_ahead = cast_ray(
+        entity,
+        position + forward * reach,
+        down,
+        monster.kind.get_half_size() + Monster::MAX_STEP_DOWN,
+        PATROL_GROUND_CG,
+        context,
+    );
+
+    grounded && !ground_ahead
+}
+
+fn cast_ray(
+    entity: Entity,
+    origin: Vec2,
+    direction: Vec2,
+    toi: f32,
+    groups: CollisionGroups,
+    context: &RapierContext,
+) -> bool {
+    let filter = QueryFilter::new()
+        .groups(groups)
+        .exclude_rigid_body(entity)
+        .exclude_sensors();
+    context
+        .cast_ray(origin, direction, toi, true, filter)
+        .is_some()
+}
+
+pub fn kill_monsters(
+    mut commands: Commands,
+    monsters: Query<(Entity, &Health), (With<Monster>, Without<MonsterDying>)>,
+    mut killed_events: EventWriter<MonsterKilled>,
+) {
+    for (entity, health) in &monsters {
+        if health.is_dead() {
+            commands.entity(entity).insert((
+                MonsterDying::default(),
+                // Keep falling on walls, but stop touching players
+                CollisionGroups::new(MONSTER_BIT, WALL_BIT),
+            ));
+            killed_events.send(MonsterKilled { monster: entity });
+        }
+    }
+}
+
+fn animate_dying_monsters(
+    mut commands: Commands,
+    mut monsters: Query<(
+        Entity,
+        &mut MonsterDying,
+        &Monster,
+        &Handle<MonsterMaterial>,
+    )>,
+    mut materials: ResMut<Assets<MonsterMaterial>>,
+    time: Res<FixedTime>,
+) {
+    for (entity, mut dying, monster, handle) in monsters.iter_mut() {
+        dying.time += time.period.as_secs_f32();
+
+        let progress = dying.time / MonsterDying::DURATION;
+
+        if progress >= 1.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let mut state = MonsterStateFlags::DYING;
+        if monster.facing_left {
+            state |= MonsterStateFlags::FACING_LEFT;
         }
 
         if let Some(m) = materials.get_mut(handle) {
-            m.state = state;
+            m.state = state.with_progress(progress);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessApp, SECOND};
+
+    #[test]
+    fn patrolling_monster_stays_on_platform() {
+        let mut app = HeadlessApp::new();
+        let mut monster = None;
+        app.build_scene(|builder| {
+            builder.spawn_wall_from_to(Vec2::new(-2.0, -1.0), Vec2::new(2.0, -2.0));
+            monster = Some(builder.spawn_monster(Vec2::new(0.0, -0.5), MonsterKind::Walker, true));
+        });
+        app.run(6 * SECOND);
+
+        let transform = app.app.world.get::<Transform>(monster.unwrap()).unwrap();
+        assert!(transform.translation.y > -1.0);
+        assert!(transform.translation.x.abs() < 2.0);
+    }
+}
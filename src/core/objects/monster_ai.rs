@@ -0,0 +1,314 @@
+use crate::core::objects::collision_groups::SIGHT_CG;
+use crate::core::objects::gravity_field::LocalGravity;
+use crate::core::objects::health::Health;
+use crate::core::objects::monster::{
+    move_monster, Monster, MonsterDamaged, MonsterDying, MonsterKind,
+};
+use crate::core::objects::player::Player;
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+
+pub struct MonsterAiPlugin;
+
+impl Plugin for MonsterAiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (update_monster_ai
+                .before(move_monster)
+                .run_if(in_state(GameWorldState::GameWorld)),)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+
+        app.add_system(
+            draw_monster_ai
+                .run_if(resource_exists::<DebugLines>())
+                .run_if(in_state(GameWorldState::GameWorld)),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MonsterState {
+    // Stands still until it sees a player
+    #[default]
+    Idle,
+    // Walks back and forth until it sees a player
+    Patrol,
+    // Noticed a player and waits a moment before chasing
+    Alert,
+    // Runs to the last position the player was seen at
+    Chase,
+    // Lost the player and goes back to where it noticed them
+    Return,
+    // Got hit and cannot move for a moment
+    Stunned,
+}
+
+impl MonsterState {
+    /// Index stored in `MonsterMaterial.state`, must match monster_material.wgsl.
+    pub fn get_index(&self) -> u32 {
+        match self {
+            MonsterState::Idle => 0,
+            MonsterState::Patrol => 1,
+            MonsterState::Alert => 2,
+            MonsterState::Chase => 3,
+            MonsterState::Return => 4,
+            MonsterState::Stunned => 5,
+        }
+    }
+
+    pub fn get_debug_color(&self) -> Color {
+        match self {
+            MonsterState::Idle => Color::GRAY,
+            MonsterState::Patrol => Color::GREEN,
+            MonsterState::Alert => Color::YELLOW,
+            MonsterState::Chase => Color::RED,
+            MonsterState::Return => Color::BLUE,
+            MonsterState::Stunned => Color::PURPLE,
+        }
+    }
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct MonsterAi {
+    pub state: MonsterState,
+    // Time spent in the current state
+    pub state_time: f32,
+    // Players closer than this are noticed, if nothing blocks the line of sight
+    pub aggro_radius: f32,
+    // Where the monster returns to after losing the player
+    pub home: Vec2,
+    pub last_seen: Option<Vec2>,
+    // Time since the player was seen last time
+    pub lost_time: f32,
+}
+
+impl MonsterAi {
+    pub const ALERT_TIME: f32 = 0.5;
+    pub const STUN_TIME: f32 = 0.6;
+    // Chase is over after not seeing the player for this long
+    pub const GIVE_UP_TIME: f32 = 3.0;
+    // Monster that could not get home for this long stays where it is
+    pub const RETURN_TIME: f32 = 6.0;
+    // Players in chase are kept track of a bit further than they are noticed
+    pub const LOSE_RADIUS_FACTOR: f32 = 1.5;
+    pub const REACH_DISTANCE: f32 = 0.3;
+
+    pub fn new(home: Vec2, kind: MonsterKind, patrol: bool) -> Self {
+        MonsterAi {
+            state: Self::get_resting_state(patrol),
+            state_time: 0.0,
+            aggro_radius: match kind {
+                MonsterKind::Flyer => 7.0,
+                _ => 5.0,
+            },
+            home,
+            last_seen: None,
+            lost_time: 0.0,
+        }
+    }
+
+    pub fn get_resting_state(patrol: bool) -> MonsterState {
+        if patrol {
+            MonsterState::Patrol
+        } else {
+            MonsterState::Idle
+        }
+    }
+
+    /// Point the monster moves to in the current state, if any.
+    pub fn get_target(&self) -> Option<Vec2> {
+        match self.state {
+            MonsterState::Chase => self.last_seen,
+            MonsterState::Return => Some(self.home),
+            _ => None,
+        }
+    }
+
+    fn set_state(&mut self, state: MonsterState) {
+        if self.state != state {
+            self.state = state;
+            self.state_time = 0.0;
+        }
+    }
+}
+
+pub fn update_monster_ai(
+    mut monsters: Query<
+        (Entity, &Monster, &mut MonsterAi, &Transform, &LocalGravity),
+        Without<MonsterDying>,
+    >,
+    players: Query<(Entity, &Transform, &Health), With<Player>>,
+    mut damaged_events: EventReader<MonsterDamaged>,
+    context: Res<RapierContext>,
+    time: Res<FixedTime>,
+) {
+    for damaged in damaged_events.iter() {
+        if let Ok((_, _, mut ai, ..)) = monsters.get_mut(damaged.monster) {
+            if let Ok((_, source, _)) = players.get(damaged.source) {
+                ai.last_seen = Some(source.translation.truncate());
+            }
+            ai.set_state(MonsterState::Stunned);
+        }
+    }
+
+    for (entity, monster, mut ai, transform, gravity) in monsters.iter_mut() {
+        ai.state_time += time.period.as_secs_f32();
+
+        let position = transform.translation.truncate();
+        let radius = match ai.state {
+            MonsterState::Chase | MonsterState::Alert => {
+                ai.aggro_radius * MonsterAi::LOSE_RADIUS_FACTOR
+            }
+            _ => ai.aggro_radius,
+        };
+
+        let seen_player = players
+            .iter()
+            .filter(|(_, _, health)| !health.is_dead())
+            .map(|(_, player, _)| player.translation.truncate())
+            .filter(|player| player.distance(position) < radius)
+            .filter(|player| is_visible(entity, position, *player, &context))
+            .min_by(|a, b| {
+                let da = a.distance_squared(position);
+                let db = b.distance_squared(position);
+                da.total_cmp(&db)
+            });
+
+        if let Some(player) = seen_player {
+            ai.last_seen = Some(player);
+            ai.lost_time = 0.0;
+        } else {
+            ai.lost_time += time.period.as_secs_f32();
+        }
+
+        let resting_state = MonsterAi::get_resting_state(monster.patrol);
+
+        match ai.state {
+            MonsterState::Idle | MonsterState::Patrol => {
+                if seen_player.is_some() {
+                    ai.home = position;
+                    ai.set_state(MonsterState::Alert);
+                }
+            }
+            MonsterState::Alert => {
+                if ai.state_time >= MonsterAi::ALERT_TIME {
+                    if seen_player.is_some() {
+                        ai.set_state(MonsterState::Chase);
+                    } else {
+                        ai.set_state(MonsterState::Return);
+                    }
+                }
+            }
+            MonsterState::Chase => {
+                if ai.lost_time >= MonsterAi::GIVE_UP_TIME {
+                    ai.last_seen = None;
+                    ai.set_state(MonsterState::Return);
+                }
+            }
+            MonsterState::Return => {
+                if seen_player.is_some() {
+                    ai.set_state(MonsterState::Alert);
+                } else if is_target_reached(monster.kind, position, ai.home, gravity.down) {
+                    ai.set_state(resting_state);
+                } else if ai.state_time >= MonsterAi::RETURN_TIME {
+                    ai.home = position;
+                    ai.set_state(resting_state);
+                }
+            }
+            MonsterState::Stunned => {
+                if ai.state_time >= MonsterAi::STUN_TIME {
+                    if ai.last_seen.is_some() && ai.lost_time < MonsterAi::GIVE_UP_TIME {
+                        ai.set_state(MonsterState::Chase);
+                    } else {
+                        ai.set_state(MonsterState::Return);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_visible(entity: Entity, from: Vec2, to: Vec2, context: &RapierContext) -> bool {
+    let filter = QueryFilter::new()
+        .groups(SIGHT_CG)
+        .exclude_rigid_body(entity)
+        .exclude_sensors();
+    context
+        .cast_ray(from, to - from, 1.0, true, filter)
+        .is_none()
+}
+
+/// Walking monsters cannot always get to the exact point, only the distance along the ground counts.
+pub fn is_target_reached(kind: MonsterKind, position: Vec2, target: Vec2, down: Vec2) -> bool {
+    let offset = target - position;
+    let distance = if kind == MonsterKind::Flyer {
+        offset.length()
+    } else {
+        offset.dot(down.perp()).abs()
+    };
+    distance < MonsterAi::REACH_DISTANCE
+}
+
+fn draw_monster_ai(
+    monsters: Query<(&MonsterAi, &Transform), Without<MonsterDying>>,
+    mut lines: ResMut<DebugLines>,
+) {
+    for (ai, transform) in &monsters {
+        let position = transform.translation.truncate();
+        let color = ai.state.get_debug_color();
+
+        let head = position + Vec2::Y * 0.7;
+        lines.line_colored(
+            head.extend(1.0),
+            (head + Vec2::Y * 0.3).extend(1.0),
+            0.0,
+            color,
+        );
+
+        if let Some(target) = ai.get_target() {
+            lines.line_colored(position.extend(1.0), target.extend(1.0), 0.0, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::objects::monster::MonsterKind;
+    use crate::core::objects::shape::PlayerShape;
+
+    fn monster_state_after(wall_between: bool) -> MonsterState {
+        let mut app = HeadlessApp::new();
+        let mut monster = None;
+        app.build_scene(|builder| {
+            builder.spawn_wall_from_to(Vec2::new(-10.0, -1.0), Vec2::new(10.0, -2.0));
+            if wall_between {
+                builder.spawn_wall_from_to(Vec2::new(-1.5, -1.0), Vec2::new(-1.0, 3.0));
+            }
+            builder.spawn_player(Vec2::new(0.0, -0.5), 0, PlayerShape::Square);
+            monster =
+                Some(builder.spawn_monster(Vec2::new(-3.0, -0.5), MonsterKind::Walker, false));
+        });
+        app.run(SECOND);
+
+        app.app
+            .world
+            .get::<MonsterAi>(monster.unwrap())
+            .unwrap()
+            .state
+    }
+
+    #[test]
+    fn monster_chases_visible_player() {
+        assert_eq!(monster_state_after(false), MonsterState::Chase);
+    }
+
+    #[test]
+    fn monster_does_not_see_player_through_wall() {
+        assert_eq!(monster_state_after(true), MonsterState::Idle);
+    }
+}
@@ -0,0 +1,554 @@
+use crate::core::objects::collision_groups::PATROL_GROUND_CG;
+use crate::core::objects::monster::{move_monster, Monster, MonsterDying, MonsterKind};
+use crate::core::objects::monster_ai::{is_target_reached, update_monster_ai, MonsterAi};
+use crate::core::objects::platform::{MovingPlatform, RotatingPlatform};
+use crate::core::scene_builder::scene_boundaries::SceneBoundaries;
+use crate::core::scene_builder::wall::Wall;
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGraph>();
+
+        app.add_system(
+            update_monster_paths
+                .after(update_monster_ai)
+                .before(move_monster)
+                .run_if(in_state(GameWorldState::GameWorld))
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+
+        app.add_system(
+            draw_monster_paths
+                .run_if(resource_exists::<DebugLines>())
+                .run_if(in_state(GameWorldState::GameWorld)),
+        );
+
+        // Shape queries see walls only after they got into the physics world
+        app.add_system(
+            rebuild_nav_graph
+                .in_base_set(CoreSet::PostUpdate)
+                .after(PhysicsSet::Writeback)
+                .run_if(in_state(GameWorldState::GameWorld))
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavLinkKind {
+    // Walk to the next cell, possibly one cell up or down
+    Walk,
+    // Walk off the ledge and fall down
+    Drop,
+    // Jump up or over a gap
+    Jump,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct NavLink {
+    pub to: IVec2,
+    pub kind: NavLinkKind,
+    // Monster must be able to jump at least this high to use the link
+    pub jump_height: f32,
+    pub cost: f32,
+}
+
+/// Walkable cells of the level grid and links between them, built for the current gravity.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct NavGraph {
+    // Grid cell (0, 0) has its bottom left corner here
+    pub origin: Vec2,
+    pub size: IVec2,
+    // Gravity direction in grid cells
+    pub down: IVec2,
+    pub solid: HashSet<IVec2>,
+    // Outgoing links of every walkable cell
+    pub links: HashMap<IVec2, Vec<NavLink>>,
+    // Increased on every rebuild, so paths know they are outdated
+    pub version: u32,
+}
+
+impl NavGraph {
+    pub const CELL_SIZE: f32 = 0.5;
+    pub const MAX_DROP_CELLS: i32 = 40;
+    pub const MAX_JUMP_CELLS_UP: i32 = 4;
+    pub const MAX_JUMP_CELLS_ACROSS: i32 = 4;
+    // Level is extended by this much when there are no scene boundaries
+    pub const WALLS_MARGIN: f32 = 4.0;
+
+    pub fn new(origin: Vec2, size: IVec2, down: IVec2, solid: HashSet<IVec2>) -> Self {
+        let mut graph = NavGraph {
+            origin,
+            size,
+            down,
+            solid,
+            ..default()
+        };
+        graph.build_links();
+        graph
+    }
+
+    pub fn get_cell(&self, position: Vec2) -> IVec2 {
+        ((position - self.origin) / Self::CELL_SIZE)
+            .floor()
+            .as_ivec2()
+    }
+
+    pub fn get_position(&self, cell: IVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + 0.5) * Self::CELL_SIZE
+    }
+
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.links.contains_key(&cell)
+    }
+
+    fn is_free(&self, cell: IVec2) -> bool {
+        cell.cmpge(IVec2::ZERO).all() && cell.cmplt(self.size).all() && !self.solid.contains(&cell)
+    }
+
+    /// Monster stands in the free cell on top of a solid one and has one more free cell above.
+    fn is_standable(&self, cell: IVec2) -> bool {
+        self.is_free(cell)
+            && self.is_free(cell - self.down)
+            && self.solid.contains(&(cell + self.down))
+    }
+
+    /// Walkable cell the body at the given position stands on or falls to.
+    pub fn find_node(&self, position: Vec2) -> Option<IVec2> {
+        let cell = self.get_cell(position);
+        for i in 0..Self::MAX_DROP_CELLS {
+            let below = cell + self.down * i;
+            if self.is_walkable(below) {
+                return Some(below);
+            }
+            if i > 0 && !self.is_free(below) {
+                break;
+            }
+        }
+        None
+    }
+
+    fn build_links(&mut self) {
+        let right = self.down.perp();
+        let mut links = HashMap::default();
+
+        for x in 0..self.size.x {
+            for y in 0..self.size.y {
+                let cell = IVec2::new(x, y);
+                if !self.is_standable(cell) {
+                    continue;
+                }
+
+                let mut cell_links = vec![];
+
+                for side in [right, -right] {
+                    let next = cell + side;
+                    if self.is_standable(next) {
+                        cell_links.push(NavLink::walk(next, 1.0));
+                    } else if self.is_standable(next - self.down)
+                        && self.is_free(cell - self.down * 2)
+                    {
+                        cell_links.push(NavLink::walk(next - self.down, 1.5));
+                    } else if self.is_standable(next + self.down) {
+                        cell_links.push(NavLink::walk(next + self.down, 1.5));
+                    } else if self.is_free(next) && self.is_free(next - self.down) {
+                        let landing = (1..Self::MAX_DROP_CELLS)
+                            .map(|i| next + self.down * i)
+                            .take_while(|cell| self.is_free(*cell))
+                            .find(|cell| self.is_standable(*cell));
+                        if let Some(landing) = landing {
+                            let cost = 1.0 + (landing - next).as_vec2().length() * 0.5;
+                            cell_links.push(NavLink {
+                                to: landing,
+                                kind: NavLinkKind::Drop,
+                                jump_height: 0.0,
+                                cost,
+                            });
+                        }
+                    }
+                }
+
+                for up in 0..=Self::MAX_JUMP_CELLS_UP {
+                    for across in -Self::MAX_JUMP_CELLS_ACROSS..=Self::MAX_JUMP_CELLS_ACROSS {
+                        // Neighbours are reached by walking
+                        if up <= 1 && across.abs() <= 1 {
+                            continue;
+                        }
+
+                        let target = cell + right * across - self.down * up;
+                        if self.is_standable(target) && self.is_jump_clear(cell, across, up) {
+                            cell_links.push(NavLink {
+                                to: target,
+                                kind: NavLinkKind::Jump,
+                                jump_height: (up + 1) as f32 * Self::CELL_SIZE,
+                                cost: 2.0 + (target - cell).as_vec2().length(),
+                            });
+                        }
+                    }
+                }
+
+                links.insert(cell, cell_links);
+            }
+        }
+
+        self.links = links;
+    }
+
+    /// Jump goes straight up one cell above the target height and then sideways to the target.
+    fn is_jump_clear(&self, from: IVec2, across: i32, up: i32) -> bool {
+        let right = self.down.perp();
+        let top = up + 2;
+        let rise_clear = (1..=top).all(|i| self.is_free(from - self.down * i));
+        let side = across.signum();
+        let flight_clear = (1..=across.abs()).all(|i| {
+            let cell = from + right * side * i - self.down * up;
+            self.is_free(cell)
+                && self.is_free(cell - self.down)
+                && self.is_free(cell - self.down * 2)
+        });
+        rise_clear && flight_clear
+    }
+
+    /// A* from one walkable cell to another, using only links the monster can jump.
+    pub fn find_path(&self, from: IVec2, to: IVec2, jump_height: f32) -> Option<Vec<NavWaypoint>> {
+        let heuristic = |cell: IVec2| (to - cell).as_vec2().length();
+
+        let mut open = BinaryHeap::new();
+        let mut came_from: HashMap<IVec2, (IVec2, NavLinkKind)> = HashMap::default();
+        let mut costs: HashMap<IVec2, f32> = HashMap::default();
+
+        open.push(OpenNode {
+            cell: from,
+            estimate: heuristic(from),
+        });
+        costs.insert(from, 0.0);
+
+        while let Some(OpenNode { cell, .. }) = open.pop() {
+            if cell == to {
+                let mut waypoints = vec![];
+                let mut current = to;
+                while let Some((previous, kind)) = came_from.get(&current) {
+                    waypoints.push(NavWaypoint {
+                        position: self.get_position(current),
+                        link: *kind,
+                    });
+                    current = *previous;
+                }
+                waypoints.reverse();
+                return Some(waypoints);
+            }
+
+            let cost = costs[&cell];
+            for link in self.links.get(&cell).into_iter().flatten() {
+                if link.jump_height > jump_height {
+                    continue;
+                }
+
+                let new_cost = cost + link.cost;
+                if costs.get(&link.to).is_none_or(|old| new_cost < *old) {
+                    costs.insert(link.to, new_cost);
+                    came_from.insert(link.to, (cell, link.kind));
+                    open.push(OpenNode {
+                        cell: link.to,
+                        estimate: new_cost + heuristic(link.to),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl NavLink {
+    fn walk(to: IVec2, cost: f32) -> Self {
+        NavLink {
+            to,
+            kind: NavLinkKind::Walk,
+            jump_height: 0.0,
+            cost,
+        }
+    }
+}
+
+struct OpenNode {
+    cell: IVec2,
+    estimate: f32,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    // Reversed, so the binary heap pops the smallest estimate first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then_with(|| (other.cell.x, other.cell.y).cmp(&(self.cell.x, self.cell.y)))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct NavWaypoint {
+    pub position: Vec2,
+    // How the monster gets to this waypoint from the previous one
+    pub link: NavLinkKind,
+}
+
+/// Route of a monster to its AI target, rebuilt when the target moves to another cell.
+#[derive(Component, Clone, Debug, Default)]
+pub struct MonsterPath {
+    pub waypoints: VecDeque<NavWaypoint>,
+    pub goal: Option<IVec2>,
+    pub graph_version: u32,
+    // Time since the path was found
+    pub age: f32,
+}
+
+impl MonsterPath {
+    pub const MAX_AGE: f32 = 1.0;
+}
+
+fn rebuild_nav_graph(
+    mut graph: ResMut<NavGraph>,
+    walls: Query<&GlobalTransform, With<Wall>>,
+    changed_walls: Query<
+        (),
+        (
+            With<Wall>,
+            Without<MovingPlatform>,
+            Without<RotatingPlatform>,
+            Or<(Changed<GlobalTransform>, Changed<CollisionGroups>)>,
+        ),
+    >,
+    mut removed_walls: RemovedComponents<Wall>,
+    moving: Query<(), Or<(With<MovingPlatform>, With<RotatingPlatform>)>>,
+    boundaries: Res<SceneBoundaries>,
+    config: Res<RapierConfiguration>,
+    context: Res<RapierContext>,
+) {
+    let down = get_grid_direction(config.gravity);
+    let walls_changed = !changed_walls.is_empty() || !removed_walls.is_empty();
+    // Removals are seen until read, so they would rebuild the graph on every step otherwise
+    removed_walls.clear();
+
+    if !walls_changed && !boundaries.is_changed() && down == graph.down {
+        return;
+    }
+
+    let rect = boundaries.rect.unwrap_or_else(|| {
+        let points: Vec<Vec2> = walls.iter().map(|t| t.translation().truncate()).collect();
+        let min = points
+            .iter()
+            .copied()
+            .fold(Vec2::splat(f32::INFINITY), Vec2::min);
+        let max = points
+            .iter()
+            .copied()
+            .fold(Vec2::splat(f32::NEG_INFINITY), Vec2::max);
+        Rect::from_corners(min, max).inset(NavGraph::WALLS_MARGIN)
+    });
+
+    if !rect.min.is_finite() || !rect.max.is_finite() {
+        return;
+    }
+
+    let size = (rect.size() / NavGraph::CELL_SIZE).ceil().as_ivec2();
+    // Empty graph just to convert cells to positions
+    let grid = NavGraph {
+        origin: rect.min,
+        size,
+        down,
+        ..default()
+    };
+
+    let predicate = |entity: Entity| !moving.contains(entity);
+    let filter = QueryFilter::new()
+        .groups(PATROL_GROUND_CG)
+        .exclude_sensors()
+        .predicate(&predicate);
+    let half = NavGraph::CELL_SIZE * 0.45;
+    let cell_shape = Collider::cuboid(half, half);
+
+    let mut solid = HashSet::default();
+    for x in 0..size.x {
+        for y in 0..size.y {
+            let cell = IVec2::new(x, y);
+            let position = grid.get_position(cell);
+            if context
+                .intersection_with_shape(position, 0.0, &cell_shape, filter)
+                .is_some()
+            {
+                solid.insert(cell);
+            }
+        }
+    }
+
+    *graph = NavGraph {
+        version: graph.version + 1,
+        ..NavGraph::new(rect.min, size, down, solid)
+    };
+}
+
+/// Gravity rounded to the nearest grid axis.
+fn get_grid_direction(gravity: Vec2) -> IVec2 {
+    if gravity.x.abs() > gravity.y.abs() {
+        IVec2::new(gravity.x.signum() as i32, 0)
+    } else {
+        IVec2::new(0, if gravity.y > 0.0 { 1 } else { -1 })
+    }
+}
+
+fn update_monster_paths(
+    mut monsters: Query<
+        (&Monster, &MonsterAi, &Transform, &mut MonsterPath),
+        Without<MonsterDying>,
+    >,
+    graph: Res<NavGraph>,
+    config: Res<RapierConfiguration>,
+    time: Res<FixedTime>,
+) {
+    for (monster, ai, transform, mut path) in monsters.iter_mut() {
+        // Flyers go straight and crawlers have their own gravity
+        if matches!(monster.kind, MonsterKind::Flyer | MonsterKind::Crawler) {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let goal = ai.get_target().and_then(|target| graph.find_node(target));
+
+        let Some(goal) = goal else {
+            *path = MonsterPath::default();
+            continue;
+        };
+
+        path.age += time.period.as_secs_f32();
+
+        if path.goal != Some(goal)
+            || path.graph_version != graph.version
+            || path.age > MonsterPath::MAX_AGE
+        {
+            let jump_height =
+                monster.kind.get_jump_speed().powi(2) / (2.0 * config.gravity.length());
+            let waypoints = graph
+                .find_node(position)
+                .and_then(|start| graph.find_path(start, goal, jump_height))
+                .unwrap_or_default();
+
+            *path = MonsterPath {
+                waypoints: waypoints.into(),
+                goal: Some(goal),
+                graph_version: graph.version,
+                age: 0.0,
+            };
+        }
+
+        // Waypoint that `move_monster` counts as reached would stop the monster if it stayed
+        let down = graph.down.as_vec2();
+        while let Some(waypoint) = path.waypoints.front() {
+            let offset = waypoint.position - position;
+            let reached = is_target_reached(monster.kind, position, waypoint.position, down)
+                && offset.dot(down).abs() < NavGraph::CELL_SIZE * 1.5;
+            if !reached {
+                break;
+            }
+            path.waypoints.pop_front();
+        }
+    }
+}
+
+fn draw_monster_paths(monsters: Query<(&MonsterPath, &Transform)>, mut lines: ResMut<DebugLines>) {
+    for (path, transform) in &monsters {
+        let mut from = transform.translation.truncate();
+        for waypoint in &path.waypoints {
+            let color = match waypoint.link {
+                NavLinkKind::Walk => Color::CYAN,
+                NavLinkKind::Drop => Color::ORANGE,
+                NavLinkKind::Jump => Color::FUCHSIA,
+            };
+            lines.line_colored(from.extend(1.0), waypoint.position.extend(1.0), 0.0, color);
+            from = waypoint.position;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::HeadlessApp;
+
+    // Floor at row 0 and a ledge two cells higher, that can be reached only by jumping
+    fn graph_with_ledge() -> NavGraph {
+        let mut solid: HashSet<IVec2> = (0..20).map(|x| IVec2::new(x, 0)).collect();
+        solid.extend((12..20).map(|x| IVec2::new(x, 3)));
+        NavGraph::new(Vec2::ZERO, IVec2::new(20, 10), IVec2::NEG_Y, solid)
+    }
+
+    #[test]
+    fn walker_cannot_path_onto_ledge() {
+        let graph = graph_with_ledge();
+        let path = graph.find_path(IVec2::new(2, 1), IVec2::new(15, 4), 0.0);
+
+        assert!(path.is_none());
+    }
+
+    #[test]
+    fn jumper_paths_onto_ledge() {
+        let graph = graph_with_ledge();
+        let path = graph
+            .find_path(IVec2::new(2, 1), IVec2::new(15, 4), 2.0)
+            .unwrap();
+
+        assert!(path
+            .iter()
+            .any(|waypoint| waypoint.link == NavLinkKind::Jump));
+        assert_eq!(
+            graph.get_cell(path.last().unwrap().position),
+            IVec2::new(15, 4)
+        );
+    }
+
+    #[test]
+    fn nav_graph_is_rebuilt_when_wall_is_despawned() {
+        let mut app = HeadlessApp::new();
+        let mut block = None;
+        app.build_scene(|builder| {
+            builder.spawn_wall_from_to(Vec2::new(-5.0, -1.0), Vec2::new(5.0, -2.0));
+            block = Some(builder.spawn_wall_from_to(Vec2::new(0.0, -1.0), Vec2::new(1.0, 0.0)));
+        });
+        app.run(3);
+
+        let block_position = Vec2::new(0.5, -0.5);
+        let graph = app.app.world.resource::<NavGraph>();
+        let version = graph.version;
+        assert!(graph.solid.contains(&graph.get_cell(block_position)));
+
+        app.app.world.despawn(block.unwrap());
+        app.run(3);
+
+        let graph = app.app.world.resource::<NavGraph>();
+        assert!(graph.version > version);
+        assert!(!graph.solid.contains(&graph.get_cell(block_position)));
+    }
+}
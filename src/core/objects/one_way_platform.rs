@@ -0,0 +1,146 @@
+use crate::core::direction::SceneDirection;
+use crate::core::objects::player::Player;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+/// Platform that bodies can jump up through and land on, "up" is against the global gravity.
+#[derive(Component, Clone, Debug, Default)]
+pub struct OneWayPlatform;
+
+/// Physics hooks of the game, passed to `RapierPhysicsPlugin`.
+#[derive(SystemParam)]
+pub struct GamePhysicsHooks<'w, 's> {
+    platforms: Query<'w, 's, (), With<OneWayPlatform>>,
+    players: Query<'w, 's, &'static Player>,
+    parents: Query<'w, 's, &'static Parent>,
+    transforms: Query<'w, 's, &'static GlobalTransform>,
+    config: Res<'w, RapierConfiguration>,
+}
+
+impl GamePhysicsHooks<'_, '_> {
+    // Angle from "up" at which contacts with a platform are still solid
+    const ALLOWED_ANGLE: f32 = 0.8;
+    // State of the contact pair that `update_as_oneway_platform` keeps in the contact user data
+    const CONTACT_FORBIDDEN: u32 = 2;
+
+    /// Player that owns the collider (player body or one of its sides).
+    fn get_player(&self, collider: Entity) -> Option<&Player> {
+        self.players.get(collider).ok().or_else(|| {
+            let parent = self.parents.get(collider).ok()?.get();
+            self.players.get(parent).ok()
+        })
+    }
+}
+
+impl BevyPhysicsHooks for GamePhysicsHooks<'_, '_> {
+    fn modify_solver_contacts(&self, context: ContactModificationContextView) {
+        let collider1 = context.collider1();
+        let collider2 = context.collider2();
+
+        let (other, platform_first) = match (
+            self.platforms.contains(collider1),
+            self.platforms.contains(collider2),
+        ) {
+            (true, false) => (collider2, true),
+            (false, true) => (collider1, false),
+            _ => return,
+        };
+
+        if let Some(player) = self.get_player(other) {
+            if player.drop_down_state.is_active {
+                // Keep the contact forbidden after the drop, until the player is out of the platform
+                *context.raw.user_data = Self::CONTACT_FORBIDDEN;
+                context.raw.solver_contacts.clear();
+                return;
+            }
+        }
+
+        let up = SceneDirection::from_gravity_direction(&self.config)
+            .get_opposite()
+            .get_vec();
+
+        // Normal goes from the first collider to the second one, in the first collider space
+        let allowed_normal = if platform_first { up } else { -up };
+        let rotation = self
+            .transforms
+            .get(collider1)
+            .map_or(Quat::IDENTITY, |transform| {
+                transform.compute_transform().rotation
+            });
+        let allowed_local_normal = (rotation.inverse() * allowed_normal.extend(0.0)).truncate();
+
+        context
+            .raw
+            .update_as_oneway_platform(&allowed_local_normal.into(), Self::ALLOWED_ANGLE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::input::PlayerAction;
+    use crate::core::objects::shape::PlayerShape;
+    use bevy::prelude::*;
+    use bevy_rapier2d::prelude::*;
+
+    // Floor, a one-way platform above it and the player, the scene is mirrored when `up` is -1.0
+    fn app_with_one_way_platform(up: f32, player_height: f32) -> HeadlessApp {
+        let mut app = HeadlessApp::new();
+        app.build_scene(|builder| {
+            builder.spawn_wall_from_to(Vec2::new(-10.0, -up), Vec2::new(10.0, -2.0 * up));
+            builder.spawn_one_way_platform(Vec2::new(-3.0, 0.1 * up), Vec2::new(3.0, 0.2 * up));
+            builder.spawn_player(Vec2::new(0.0, player_height * up), 0, PlayerShape::Square);
+        });
+        app.app.world.resource_mut::<RapierConfiguration>().gravity = Vec2::NEG_Y * 9.8 * up;
+        app
+    }
+
+    fn check_jump_up_through_one_way_platform(up: f32) {
+        let mut app = app_with_one_way_platform(up, -0.5);
+        app.run(SECOND / 2);
+        app.script(0, &[PlayerAction::Jump], SECOND / 10);
+        app.run(2 * SECOND);
+
+        let player = app.get_player(0);
+        let height = app.get_player_transform(0).translation.y * up;
+
+        // Player stands on top of the platform
+        assert!(player.landed_state.is_active);
+        assert!((height - 0.7).abs() < 0.1);
+    }
+
+    fn check_drop_down_through_one_way_platform(up: f32) {
+        let mut app = app_with_one_way_platform(up, 0.7);
+        app.run(SECOND / 2);
+
+        assert!(app.get_player_transform(0).translation.y * up > 0.6);
+
+        app.script(0, &[PlayerAction::DropDown], SECOND / 10);
+        app.run(SECOND);
+
+        // Player fell through the platform to the floor
+        let height = app.get_player_transform(0).translation.y * up;
+        assert!((height - -0.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn player_jumps_up_through_one_way_platform() {
+        check_jump_up_through_one_way_platform(1.0);
+    }
+
+    #[test]
+    fn player_drops_down_through_one_way_platform() {
+        check_drop_down_through_one_way_platform(1.0);
+    }
+
+    #[test]
+    fn player_jumps_up_through_one_way_platform_with_flipped_gravity() {
+        check_jump_up_through_one_way_platform(-1.0);
+    }
+
+    #[test]
+    fn player_drops_down_through_one_way_platform_with_flipped_gravity() {
+        check_drop_down_through_one_way_platform(-1.0);
+    }
+}
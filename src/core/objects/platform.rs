@@ -0,0 +1,195 @@
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+pub struct PlatformPlugin;
+
+impl Plugin for PlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                move_platforms.run_if(in_state(GameWorldState::GameWorld)),
+                rotate_platforms.run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PathMode {
+    // A -> B -> C -> B -> A -> ...
+    #[default]
+    PingPong,
+    // A -> B -> C -> A -> ...
+    Loop,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PlatformPath {
+    pub waypoints: Vec<Vec2>,
+    #[serde(default)]
+    pub mode: PathMode,
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+    // Seconds to wait at every waypoint
+    #[serde(default)]
+    pub pause: f32,
+}
+
+fn default_speed() -> f32 {
+    1.5
+}
+
+/// Kinematic platform that moves along the path, starting at its first waypoint.
+#[derive(Component, Clone, Debug)]
+pub struct MovingPlatform {
+    pub path: PlatformPath,
+    pub next: usize,
+    pub forward: bool,
+    pub pause_left: f32,
+}
+
+impl MovingPlatform {
+    pub fn new(path: PlatformPath) -> Self {
+        MovingPlatform {
+            next: 1.min(path.waypoints.len().saturating_sub(1)),
+            path,
+            forward: true,
+            pause_left: 0.0,
+        }
+    }
+
+    fn advance(&mut self) {
+        let n = self.path.waypoints.len();
+        if n < 2 {
+            return;
+        }
+
+        match self.path.mode {
+            PathMode::Loop => self.next = (self.next + 1) % n,
+            PathMode::PingPong => {
+                if self.forward && self.next + 1 == n || !self.forward && self.next == 0 {
+                    self.forward = !self.forward;
+                }
+                if self.forward {
+                    self.next += 1;
+                } else {
+                    self.next -= 1;
+                }
+            }
+        }
+    }
+}
+
+/// Kinematic platform that rotates around its center.
+#[derive(Component, Clone, Debug)]
+pub struct RotatingPlatform {
+    pub angular_speed: f32,
+}
+
+fn move_platforms(
+    mut platforms: Query<(&mut MovingPlatform, &Transform, &mut Velocity)>,
+    time: Res<FixedTime>,
+) {
+    let dt = time.period.as_secs_f32();
+    if dt <= 0.0 {
+        return;
+    }
+
+    for (mut platform, transform, mut velocity) in platforms.iter_mut() {
+        if platform.pause_left > 0.0 {
+            platform.pause_left -= dt;
+            velocity.linvel = Vec2::ZERO;
+            continue;
+        }
+
+        let Some(target) = platform.path.waypoints.get(platform.next).copied() else {
+            velocity.linvel = Vec2::ZERO;
+            continue;
+        };
+
+        let offset = target - transform.translation.truncate();
+        let step = platform.path.speed * dt;
+
+        if offset.length() <= step {
+            // Reach the waypoint exactly this step
+            velocity.linvel = offset / dt;
+            platform.pause_left = platform.path.pause;
+            platform.advance();
+        } else {
+            velocity.linvel = offset.normalize() * platform.path.speed;
+        }
+    }
+}
+
+fn rotate_platforms(mut platforms: Query<(&RotatingPlatform, &mut Velocity)>) {
+    for (platform, mut velocity) in platforms.iter_mut() {
+        velocity.angvel = platform.angular_speed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::objects::shape::PlayerShape;
+    use crate::core::objects::side_effect::SideEffect;
+
+    fn app_with_moving_platform(
+        size: Vec2,
+        waypoints: Vec<Vec2>,
+        speed: f32,
+        player_position: Vec2,
+    ) -> HeadlessApp {
+        let mut app = HeadlessApp::new();
+        app.build_scene(|builder| {
+            let path = PlatformPath {
+                waypoints,
+                mode: PathMode::PingPong,
+                speed,
+                pause: 0.0,
+            };
+            builder.spawn_moving_platform(size, path);
+            builder.spawn_player(player_position, 0, PlayerShape::Square);
+        });
+        app
+    }
+
+    #[test]
+    fn player_rides_moving_platform() {
+        let mut app = app_with_moving_platform(
+            Vec2::new(3.0, 0.5),
+            vec![Vec2::new(0.0, -1.25), Vec2::new(6.0, -1.25)],
+            1.5,
+            Vec2::new(0.0, -0.5),
+        );
+        app.run(2 * SECOND);
+
+        // Platform is at x = 3.0 by now
+        let transform = app.get_player_transform(0);
+        assert!(transform.translation.x > 2.0);
+        assert!(transform.translation.y > -1.0);
+    }
+
+    #[test]
+    fn sticky_side_follows_moving_platform() {
+        let mut app = app_with_moving_platform(
+            Vec2::new(1.0, 4.0),
+            vec![Vec2::new(1.0, 0.0), Vec2::new(4.0, 0.0)],
+            0.25,
+            Vec2::new(-0.01, 0.0),
+        );
+        // Side 1 of the square looks right, to the platform that moves away from it
+        app.set_player_effect(0, 1, SideEffect::Sticky);
+        app.run(SECOND);
+
+        let player = app.get_player(0);
+        let transform = app.get_player_transform(0);
+
+        // Platform moved by 0.25 and the player went along instead of falling straight down
+        assert!(player.stick_to_wall_state.is_active);
+        assert!(transform.translation.x > 0.2);
+    }
+}
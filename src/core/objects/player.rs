@@ -1,21 +1,28 @@
-use crate::core::direction::SceneDirection;
+use crate::core::input::{ActionState, InputSet, PlayerAction};
+use crate::core::objects::gravity_field::{GravitySet, LocalGravity};
+use crate::core::objects::one_way_platform::OneWayPlatform;
+use crate::core::objects::respawn::Respawning;
 use std::f32::consts::PI;
 
 use crate::states::GameWorldState;
 use bevy::prelude::*;
 
 use crate::core::objects::collision_groups::PLAYER_CG;
-use crate::core::objects::shape::MAX_SIDES;
+use crate::core::objects::shape::{PlayerShape, MAX_SIDES};
 use crate::core::objects::side_effect::SideEffect;
 
 use crate::core::materials::player_material::PlayerMaterial;
 use bevy_rapier2d::prelude::*;
 
+// Steeper surfaces are treated as walls
+const MAX_SLOPE_ANGLE: f32 = 0.9;
+
 #[derive(Component, Clone, Debug, Default)]
 pub struct Player {
     pub id: u32,
+    pub shape: PlayerShape,
 
-    pub moving_state: PlayerState, // Player presses A or D
+    pub moving_state: PlayerState, // Player presses MoveLeft or MoveRight
 
     pub landed_state: PlayerState,
     // Player has something below
@@ -26,6 +33,10 @@ pub struct Player {
 
     pub slippery_below_state: PlayerState,
 
+    pub spring_state: PlayerState, // Spring side launched the player this frame
+
+    pub drop_down_state: PlayerState, // Player falls through one-way platforms
+
     pub time_since_last_spin: f32,
     pub time_since_last_jump: f32,
 
@@ -43,8 +54,8 @@ pub struct PlayerState {
 }
 
 impl PlayerState {
-    pub fn advance(&mut self, time: &Time) {
-        self.time_since_changed += time.delta_seconds();
+    pub fn advance(&mut self, time: &FixedTime) {
+        self.time_since_changed += time.period.as_secs_f32();
     }
 
     pub fn set(&mut self, active: bool) {
@@ -90,11 +101,17 @@ pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems((
-            move_player.run_if(in_state(GameWorldState::GameWorld)),
-            update_side_effects.run_if(in_state(GameWorldState::GameWorld)),
-            update_side_entities.run_if(in_state(GameWorldState::GameWorld)),
-        ));
+        app.add_systems(
+            (
+                move_player
+                    .after(InputSet)
+                    .after(GravitySet)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+                update_side_effects.run_if(in_state(GameWorldState::GameWorld)),
+                update_side_entities.run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
     }
 }
 
@@ -123,6 +140,22 @@ impl Player {
         5.5
     }
 
+    pub fn get_spring_launch_speed(&self, impact_speed: f32) -> f32 {
+        (3.0 + impact_speed * 0.8).min(9.0)
+    }
+
+    pub fn get_spring_min_impact_speed(&self) -> f32 {
+        1.0
+    }
+
+    pub fn get_drop_down_time(&self) -> f32 {
+        0.3
+    }
+
+    pub fn get_spring_cooldown(&self) -> f32 {
+        0.25
+    }
+
     pub fn get_friction(&self) -> f32 {
         0.2
     }
@@ -131,18 +164,102 @@ impl Player {
         Vec2::new(0.0, 0.0)
     }
 
+    pub fn get_number_of_sides(&self) -> usize {
+        self.shape.number_of_sides() as usize
+    }
+
     pub fn get_side_centers(&self) -> Vec<Vec2> {
-        return vec![
-            Vec2::NEG_Y * 0.5,
-            Vec2::X * 0.5,
-            Vec2::Y * 0.5,
-            Vec2::NEG_X * 0.5,
-        ];
+        self.shape.get_side_centers()
     }
 
     pub fn get_side_directions(&self) -> Vec<Vec2> {
-        return vec![Vec2::NEG_Y, Vec2::X, Vec2::Y, Vec2::NEG_X];
+        self.shape.get_side_directions()
     }
+
+    /// Moves every effect to the free side of `shape` with the closest direction.
+    /// Sides are processed in order, effects that find no free side are lost.
+    pub fn remap_effects(&self, shape: PlayerShape) -> [SideEffect; MAX_SIDES] {
+        let mut effects = [SideEffect::None; MAX_SIDES];
+        let new_directions = shape.get_side_directions();
+
+        for (i, direction) in self.get_side_directions().iter().enumerate() {
+            if self.effects[i] == SideEffect::None {
+                continue;
+            }
+
+            let mut best: Option<(usize, f32)> = None;
+            for (j, new_direction) in new_directions.iter().enumerate() {
+                if effects[j] != SideEffect::None {
+                    continue;
+                }
+                let dot = direction.dot(*new_direction);
+                // Epsilon keeps the lower index on ties
+                if best.is_none_or(|(_, best_dot)| dot > best_dot + 1e-4) {
+                    best = Some((j, dot));
+                }
+            }
+
+            if let Some((j, _)) = best {
+                effects[j] = self.effects[i];
+            }
+        }
+
+        effects
+    }
+
+    /// Index of the side whose world direction is closest to `direction`.
+    pub fn get_side_facing(&self, transform: &Transform, direction: Vec2) -> usize {
+        let rotation = Vec2::from_angle(get_angle_from_quat(transform.rotation));
+        let mut best_side = 0;
+        let mut best_dot = f32::MIN;
+        for (i, side_direction) in self.get_side_directions().iter().enumerate() {
+            let dot = rotation.rotate(*side_direction).dot(direction);
+            if dot > best_dot {
+                best_dot = dot;
+                best_side = i;
+            }
+        }
+        best_side
+    }
+}
+
+/// Spawns thin colliders along every side of `shape`, they carry per-side friction.
+pub fn spawn_player_sides(
+    commands: &mut Commands,
+    parent: Entity,
+    shape: PlayerShape,
+) -> [Option<Entity>; MAX_SIDES] {
+    let mut side_entities = [None; MAX_SIDES];
+
+    let directions = shape.get_side_directions();
+    let side_length = shape.get_side_length(0.5);
+
+    for i in 0..shape.number_of_sides() as usize {
+        let child = commands
+            .spawn((
+                VisibilityBundle::default(),
+                TransformBundle::from_transform(
+                    Transform::from_translation(directions[i].extend(0.0) * 0.475).with_rotation(
+                        Quat::from_axis_angle(Vec3::Z, shape.get_side_angle(i) + PI * 0.5),
+                    ),
+                ),
+                Collider::round_cuboid(side_length * 0.45, 0.01, 0.01),
+                Friction {
+                    coefficient: 0.3,
+                    combine_rule: CoefficientCombineRule::Min,
+                },
+                Restitution {
+                    coefficient: 0.0,
+                    combine_rule: CoefficientCombineRule::Max,
+                },
+                PLAYER_CG,
+            ))
+            .id();
+        side_entities[i] = Some(child);
+        commands.entity(parent).add_child(child);
+    }
+
+    side_entities
 }
 
 fn update_side_effects(
@@ -154,6 +271,7 @@ fn update_side_effects(
             for i in 0..MAX_SIDES {
                 material.effect_index[i as usize] = player.effects[i as usize].to_index();
             }
+            material.number_of_sides = player.shape.number_of_sides();
         }
     }
 }
@@ -163,20 +281,20 @@ fn update_side_entities(
     mut sides: Query<(&mut Friction, &mut Restitution), Without<Player>>,
 ) {
     for player in &players {
-        for i in 0..MAX_SIDES {
+        for i in 0..player.get_number_of_sides() {
             let f_coefficient = match player.effects[i] {
                 SideEffect::Sticky => 0.6,
                 SideEffect::Slippery => 0.02,
                 _ => 0.3,
             };
-            let r_coefficient = match player.effects[i] {
-                SideEffect::Spring => 0.75,
-                _ => 0.0,
+            // Spring launch is applied in `move_player`, restitution would only add noise to it
+            let r_coefficient = 0.0;
+
+            let Some(side_entity) = player.side_entities[i] else {
+                continue;
             };
 
-            if let Ok((mut friction, mut restitution)) =
-                sides.get_mut(player.side_entities[i].unwrap())
-            {
+            if let Ok((mut friction, mut restitution)) = sides.get_mut(side_entity) {
                 if friction.coefficient != f_coefficient {
                     friction.coefficient = f_coefficient;
                 }
@@ -189,55 +307,116 @@ fn update_side_entities(
 }
 
 fn move_player(
-    mut players: Query<(
-        Entity,
-        &mut ExternalImpulse,
-        &Velocity,
-        &ReadMassProperties,
-        &mut Player,
-        &Transform,
-    )>,
-    mut objects: Query<(&mut ExternalImpulse, &Transform, &ReadMassProperties), Without<Player>>,
-    keys: Res<Input<KeyCode>>,
-    time: Res<Time>,
-    config: ResMut<RapierConfiguration>,
+    mut players: Query<
+        (
+            Entity,
+            &mut ExternalImpulse,
+            &Velocity,
+            &ReadMassProperties,
+            &mut Player,
+            &Transform,
+            &ActionState,
+            &LocalGravity,
+        ),
+        Without<Respawning>,
+    >,
+    mut objects: Query<
+        (
+            Option<&mut ExternalImpulse>,
+            &Transform,
+            Option<&ReadMassProperties>,
+            Option<&Velocity>,
+        ),
+        Without<Player>,
+    >,
+    one_way_platforms: Query<(), With<OneWayPlatform>>,
+    time: Res<FixedTime>,
     context: Res<RapierContext>,
 ) {
-    let gravity_direction = SceneDirection::from_gravity_direction(&config);
-
-    for (entity, mut impulse, velocity, mass, mut player, transform) in players.iter_mut() {
+    for (entity, mut impulse, velocity, mass, mut player, transform, actions, gravity) in
+        players.iter_mut()
+    {
         let player: &mut Player = &mut player;
+        let down = gravity.down;
 
         player.moving_state.advance(&time);
         player.landed_state.advance(&time);
         player.in_air_state.advance(&time);
         player.stick_to_wall_state.advance(&time);
         player.stick_to_anything_state.advance(&time);
-        player.time_since_last_spin += time.delta_seconds();
-        player.time_since_last_jump += time.delta_seconds();
+        player.spring_state.advance(&time);
+        player.drop_down_state.advance(&time);
+        player.time_since_last_spin += time.period.as_secs_f32();
+        player.time_since_last_jump += time.period.as_secs_f32();
+
+        let collider_below = find_obstacle(
+            entity,
+            &player.small_collider,
+            down,
+            transform,
+            &context,
+            0.027,
+        );
+
+        let on_one_way_platform =
+            collider_below.is_some_and(|(other, _)| one_way_platforms.contains(other));
+
+        // Fall through one-way platform
+        if actions.just_pressed(PlayerAction::DropDown) && on_one_way_platform {
+            player.drop_down_state.activate();
+        } else if player.drop_down_state.time_since_activated() > player.get_drop_down_time()
+            && !touches_one_way_platform(entity, player, &context, &one_way_platforms)
+        {
+            // Colliders still inside the platform would stand on it when the contact is allowed
+            player.drop_down_state.deactivate();
+        }
+
+        // Player does not stand on one-way platform while passing through it
+        let collider_below = collider_below.filter(|(other, _)| {
+            !one_way_platforms.contains(*other)
+                || !player.drop_down_state.is_active && velocity.linvel.dot(down) > -0.1
+        });
+
+        // Velocity of the ground (e.g. moving platform) under the player
+        let ground_velocity = collider_below
+            .and_then(|(other, _)| objects.get(other).ok())
+            .map_or(Vec2::ZERO, |(_, transform2, _, velocity2)| {
+                velocity2.map_or(Vec2::ZERO, |v| {
+                    get_point_velocity(v, transform2, transform.translation.truncate())
+                })
+            });
+
+        // "Down" along the walkable surface under the player, e.g. a slope
+        let surface_down = collider_below
+            .and_then(|_| find_surface_normal(entity, down, transform, &context))
+            .map(|normal| -normal)
+            .filter(|surface_down| surface_down.angle_between(down).abs() < MAX_SLOPE_ANGLE)
+            .unwrap_or(down);
 
         // Move left-right
         {
+            let right = surface_down.perp();
             let mut target_velocity = 0.0;
-            let right = gravity_direction.get_vec().perp();
 
-            if keys.any_pressed([KeyCode::A]) {
+            if actions.pressed(PlayerAction::MoveLeft) {
                 player.moving_state.activate();
                 target_velocity -= player.get_max_speed();
             }
 
-            if keys.any_pressed([KeyCode::D]) {
+            if actions.pressed(PlayerAction::MoveRight) {
                 player.moving_state.activate();
                 target_velocity += player.get_max_speed();
             }
 
-            let mut delta_velocity = target_velocity - velocity.linvel.dot(right);
+            // Move relative to the ground, so moving platforms carry the player
+            let mut delta_velocity =
+                target_velocity + ground_velocity.dot(right) - velocity.linvel.dot(right);
 
             // For more "snappy" moves
             let bonus = ((delta_velocity.abs() - player.get_max_speed()) / player.get_max_speed())
                 .clamp(0.0, 2.0);
 
-            let limit = player.get_max_acceleration() * time.delta_seconds() * (1.0 + bonus);
+            let limit = player.get_max_acceleration() * time.period.as_secs_f32() * (1.0 + bonus);
 
             delta_velocity = delta_velocity.clamp(-limit, limit);
 
@@ -260,10 +439,10 @@ fn move_player(
 
         // Schedule spin
         let force_jump = {
-            if keys.just_pressed(KeyCode::W) {
+            if actions.just_pressed(PlayerAction::SpinCCW) {
                 player.delayed_spin_torque = Some(3.0);
                 true
-            } else if keys.just_pressed(KeyCode::S) {
+            } else if actions.just_pressed(PlayerAction::SpinCW) {
                 player.delayed_spin_torque = Some(-3.0);
                 true
             } else {
@@ -286,15 +465,19 @@ fn move_player(
             }
         }
 
-        // Snap to 90-s angles
+        // Snap to the angles where some side looks along gravity (or lies on the slope)
         {
+            let snap_angle = player.shape.get_snap_angle();
+            let gravity_angle = Vec2::NEG_Y.angle_between(surface_down);
             let angle: f32 =
                 (get_angle_from_quat(transform.rotation) % (2.0 * PI) + 2.0 * PI) % (2.0 * PI);
-            let target_angle = ((angle + velocity.angvel * 0.3) / (PI * 0.5)).round() * (PI * 0.5);
+            let target_angle =
+                ((angle + velocity.angvel * 0.3 - gravity_angle) / snap_angle).round() * snap_angle
+                    + gravity_angle;
 
             let mut delta_angle = target_angle - angle;
 
-            let limit = player.get_max_angular_acceleration() * time.delta_seconds() * 2.0;
+            let limit = player.get_max_angular_acceleration() * time.period.as_secs_f32() * 2.0;
 
             delta_angle = delta_angle.clamp(-limit, limit);
 
@@ -306,7 +489,7 @@ fn move_player(
                 5.0
             };
 
-            // Apply torque in direction of nearest right angle
+            // Apply torque in direction of nearest snap angle
             impulse.torque_impulse += delta_angle * mass.0.principal_inertia * multiplier;
             // Apply damping torque
             impulse.torque_impulse -= 0.2 * velocity.angvel * mass.0.principal_inertia * multiplier;
@@ -314,32 +497,25 @@ fn move_player(
 
         // Jump
         {
-            let collider_below = find_obstacle(
-                entity,
-                &player.small_collider,
-                gravity_direction.get_vec(),
-                transform,
-                &context,
-                0.027,
-            );
-
             let collider_right = find_obstacle(
                 entity,
                 &player.small_collider,
-                gravity_direction.get_vec().perp(),
+                down.perp(),
                 transform,
                 &context,
                 0.027,
-            );
+            )
+            .filter(|(other, _)| !one_way_platforms.contains(*other));
 
             let collider_left = find_obstacle(
                 entity,
                 &player.small_collider,
-                -gravity_direction.get_vec().perp(),
+                -down.perp(),
                 transform,
                 &context,
                 0.027,
-            );
+            )
+            .filter(|(other, _)| !one_way_platforms.contains(*other));
 
             if collider_below.is_some() {
                 player.landed_state.activate();
@@ -349,7 +525,7 @@ fn move_player(
                 player.in_air_state.activate();
             }
 
-            if keys.any_pressed([KeyCode::Space]) || force_jump {
+            if actions.pressed(PlayerAction::Jump) || force_jump {
                 if (player.landed_state.time_since_activated() > 0.05
                     || player.stick_to_wall_state.time_since_activated() > 0.05)
                     && player.time_since_last_jump > 0.2
@@ -358,14 +534,14 @@ fn move_player(
                     player.in_air_state.activate();
                     player.time_since_last_jump = 0.0;
 
-                    let mut dir = -gravity_direction.get_vec();
+                    let mut dir = -down;
 
                     // Side jump if stick to wall
                     if collider_right.is_some()
                         && player.stick_to_wall_state.time_since_activated() > 0.05
                     {
                         dir *= 1.0;
-                        dir += -gravity_direction.get_vec().perp() * 0.5;
+                        dir += -down.perp() * 0.5;
                     }
 
                     // Side jump if stick to wall
@@ -373,7 +549,7 @@ fn move_player(
                         && player.stick_to_wall_state.time_since_activated() > 0.05
                     {
                         dir *= 1.0;
-                        dir += gravity_direction.get_vec().perp() * 0.5;
+                        dir += down.perp() * 0.5;
                     }
 
                     impulse.impulse += dir * player.get_jump_impulse() * mass.0.mass;
@@ -384,10 +560,13 @@ fn move_player(
         let mut stick_to_wall = false;
         let mut stick_to_something = false;
         let mut slippery_below = false;
+        let mut spring_launched = false;
+
+        let side_directions = player.get_side_directions();
 
-        for i in 0..MAX_SIDES {
+        for i in 0..player.get_number_of_sides() {
             let dir = Vec2::from_angle(get_angle_from_quat(transform.rotation))
-                .rotate(player.get_side_directions()[i]);
+                .rotate(side_directions[i]);
 
             let collider_nearby = find_obstacle(
                 entity,
@@ -418,19 +597,32 @@ fn move_player(
 
                         // Apply impulse to player
                         *impulse += ExternalImpulse::at_point(
-                            dir * stick_force * mass.0.mass * time.delta_seconds(),
+                            dir * stick_force * mass.0.mass * time.period.as_secs_f32(),
                             transform.translation.truncate(),
                             transform.translation.truncate(),
                         );
-                        // Apply impulse to other body
-                        if let Ok((mut impulse2, transform2, mass2)) =
+                        if let Ok((impulse2, transform2, mass2, velocity2)) =
                             objects.get_mut(collider_nearby.unwrap().0)
                         {
-                            *impulse2 += ExternalImpulse::at_point(
-                                -dir * stick_force * mass2.0.mass * time.delta_seconds(),
-                                transform.translation.truncate(),
-                                transform2.translation.truncate(),
-                            );
+                            if let (Some(mut impulse2), Some(mass2)) = (impulse2, mass2) {
+                                // Apply impulse to other body
+                                *impulse2 += ExternalImpulse::at_point(
+                                    -dir * stick_force * mass2.0.mass * time.period.as_secs_f32(),
+                                    transform.translation.truncate(),
+                                    transform2.translation.truncate(),
+                                );
+                            } else if let Some(velocity2) = velocity2 {
+                                // Kinematic body can not be pulled, follow it if it moves away
+                                let point_velocity = get_point_velocity(
+                                    velocity2,
+                                    transform2,
+                                    transform.translation.truncate(),
+                                );
+                                let catch_up = (point_velocity - velocity.linvel).dot(dir);
+                                if catch_up > 0.0 {
+                                    impulse.impulse += dir * catch_up * mass.0.mass;
+                                }
+                            }
                         }
                     }
                 }
@@ -443,16 +635,70 @@ fn move_player(
                 SideEffect::Thorns => {}
                 SideEffect::Flashlight => {}
                 SideEffect::Laser => {}
-                SideEffect::Spring => {}
+                SideEffect::Spring => {
+                    let ready = !player.spring_state.is_active
+                        && player.spring_state.time_since_deactivated()
+                            > player.get_spring_cooldown();
+
+                    if let (Some((other, _)), true) = (collider_nearby, ready) {
+                        let other_velocity = objects
+                            .get(other)
+                            .ok()
+                            .and_then(|(_, _, _, v)| v.copied())
+                            .unwrap_or_default();
+
+                        // How fast this side approaches the obstacle
+                        let impact_speed = (velocity.linvel - other_velocity.linvel).dot(dir);
+
+                        if impact_speed > player.get_spring_min_impact_speed() {
+                            spring_launched = true;
+                            let launch_speed = player.get_spring_launch_speed(impact_speed);
+
+                            // Replace velocity along the side normal, so the launch does not depend on the approach
+                            impulse.impulse +=
+                                -dir * (launch_speed + velocity.linvel.dot(dir)) * mass.0.mass;
+
+                            // Push dynamic bodies away
+                            if let Ok((Some(mut impulse2), _, Some(mass2), _)) =
+                                objects.get_mut(other)
+                            {
+                                impulse2.impulse += dir * launch_speed * mass2.0.mass;
+                            }
+                        }
+                    }
+                }
             }
         }
 
         player.stick_to_wall_state.set(stick_to_wall);
         player.stick_to_anything_state.set(stick_to_something);
         player.slippery_below_state.set(slippery_below);
+        player.spring_state.set(spring_launched);
     }
 }
 
+/// Normal of the surface right under the player center.
+fn find_surface_normal(
+    entity: Entity,
+    down: Vec2,
+    transform: &Transform,
+    context: &RapierContext,
+) -> Option<Vec2> {
+    let filter = QueryFilter::new()
+        .groups(PLAYER_CG)
+        .exclude_rigid_body(entity)
+        .exclude_sensors();
+    context
+        .cast_ray_and_get_normal(transform.translation.truncate(), down, 1.0, true, filter)
+        .map(|(_, intersection)| intersection.normal)
+}
+
+/// Velocity of the point of a rigid body, including its rotation.
+fn get_point_velocity(velocity: &Velocity, transform: &Transform, point: Vec2) -> Vec2 {
+    let offset = point - transform.translation.truncate();
+    velocity.linvel + offset.perp() * velocity.angvel
+}
+
 fn get_angle_from_quat(q: Quat) -> f32 {
     let (axis, angle) = q.to_axis_angle();
     if axis.dot(Vec3::Z) < 0.0 {
@@ -462,6 +708,23 @@ fn get_angle_from_quat(q: Quat) -> f32 {
     }
 }
 
+/// Body or any side of the player touches a one-way platform.
+fn touches_one_way_platform(
+    entity: Entity,
+    player: &Player,
+    context: &RapierContext,
+    one_way_platforms: &Query<(), With<OneWayPlatform>>,
+) -> bool {
+    std::iter::once(entity)
+        .chain(player.side_entities.iter().flatten().copied())
+        .flat_map(|collider| context.contacts_with(collider))
+        .filter(|pair| pair.has_any_active_contacts())
+        .any(|pair| {
+            one_way_platforms.contains(pair.collider1())
+                || one_way_platforms.contains(pair.collider2())
+        })
+}
+
 fn find_obstacle(
     entity: Entity,
     collider: &Collider,
@@ -484,3 +747,75 @@ fn find_obstacle(
         )
         .map(|(e, toi)| (e, toi.toi))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::input::PlayerAction;
+    use crate::core::objects::shape::PlayerShape;
+    use crate::core::objects::side_effect::SideEffect;
+    use bevy::prelude::*;
+
+    fn app_with_floor(player_position: Vec2) -> HeadlessApp {
+        let mut app = HeadlessApp::new();
+        app.build_scene(|builder| {
+            builder.spawn_wall_from_to(Vec2::new(-10.0, -1.0), Vec2::new(10.0, -2.0));
+            builder.spawn_player(player_position, 0, PlayerShape::Square);
+        });
+        app
+    }
+
+    #[test]
+    fn player_lands_on_floor() {
+        let mut app = app_with_floor(Vec2::new(0.0, 1.0));
+        app.run(2 * SECOND);
+
+        let player = app.get_player(0);
+        let transform = app.get_player_transform(0);
+
+        assert!(player.landed_state.is_active);
+        assert!((transform.translation.y - -0.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn player_moves_right() {
+        let mut app = app_with_floor(Vec2::new(0.0, -0.5));
+        app.run(SECOND / 2);
+        app.script(0, &[PlayerAction::MoveRight], SECOND);
+        app.run(SECOND);
+
+        assert!(app.get_player_transform(0).translation.x > 1.0);
+    }
+
+    fn app_next_to_wall(effect: SideEffect) -> HeadlessApp {
+        let mut app = HeadlessApp::new();
+        app.build_scene(|builder| {
+            builder.spawn_wall_from_to(Vec2::new(0.5, -20.0), Vec2::new(1.5, 20.0));
+            builder.spawn_player(Vec2::new(-0.01, 0.0), 0, PlayerShape::Square);
+        });
+        app.run(1);
+        // Side 1 of the square looks right, to the wall
+        app.set_player_effect(0, 1, effect);
+        app
+    }
+
+    #[test]
+    fn sticky_side_holds_player_on_wall() {
+        let mut app = app_next_to_wall(SideEffect::Sticky);
+        app.run(2 * SECOND);
+
+        let player = app.get_player(0);
+        let transform = app.get_player_transform(0);
+
+        assert!(player.stick_to_wall_state.is_active);
+        assert!(transform.translation.y > -0.5);
+    }
+
+    #[test]
+    fn player_without_sticky_side_falls_along_wall() {
+        let mut app = app_next_to_wall(SideEffect::None);
+        app.run(2 * SECOND);
+
+        assert!(app.get_player_transform(0).translation.y < -5.0);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::core::deterministic::FixedEventApp;
+use crate::core::objects::health::{Health, PlayerDied};
+use crate::core::objects::monster::{Monster, MonsterDying};
+use crate::core::objects::player::Player;
+use crate::core::scene_builder::scene_boundaries::SceneBoundaries;
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_fixed_event::<PlayerRespawned>();
+
+        app.add_systems(
+            (
+                kill_plane.run_if(in_state(GameWorldState::GameWorld)),
+                start_respawn
+                    .after(kill_plane)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+                respawn_players
+                    .after(start_respawn)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// Place where the player appears again after death.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct SpawnPoint(pub Vec2);
+
+/// Dead player waiting to be respawned, it is frozen and hidden meanwhile.
+#[derive(Component, Clone, Debug, Default)]
+pub struct Respawning {
+    pub time: f32,
+}
+
+impl Respawning {
+    pub const DELAY: f32 = 1.0;
+}
+
+/// Player appeared at its spawn point with full health.
+#[derive(Clone, Debug)]
+pub struct PlayerRespawned {
+    pub player: Entity,
+}
+
+// Bodies this far outside of the scene boundaries are killed
+const KILL_PLANE_MARGIN: f32 = 3.0;
+
+fn kill_plane(
+    mut players: Query<(Entity, &Transform, &mut Health), (With<Player>, Without<Respawning>)>,
+    mut monsters: Query<
+        (&Transform, &mut Health),
+        (With<Monster>, Without<MonsterDying>, Without<Player>),
+    >,
+    boundaries: Res<SceneBoundaries>,
+    mut died_events: EventWriter<PlayerDied>,
+) {
+    let Some(rect) = boundaries.rect else {
+        return;
+    };
+
+    let is_out = |transform: &Transform| {
+        let position = transform.translation.truncate();
+        position.cmplt(rect.min - KILL_PLANE_MARGIN).any()
+            || position.cmpgt(rect.max + KILL_PLANE_MARGIN).any()
+    };
+
+    for (entity, transform, mut health) in players.iter_mut() {
+        if is_out(transform) && !health.is_dead() {
+            health.current = 0.0;
+            died_events.send(PlayerDied { player: entity });
+        }
+    }
+
+    for (transform, mut health) in monsters.iter_mut() {
+        if is_out(transform) {
+            health.current = 0.0;
+        }
+    }
+}
+
+fn start_respawn(
+    mut commands: Commands,
+    mut died_events: EventReader<PlayerDied>,
+    mut players: Query<(&mut RigidBody, &mut Visibility), (With<Player>, Without<Respawning>)>,
+) {
+    for died in died_events.iter() {
+        if let Ok((mut body, mut visibility)) = players.get_mut(died.player) {
+            *body = RigidBody::Fixed;
+            *visibility = Visibility::Hidden;
+            commands.entity(died.player).insert(Respawning::default());
+        }
+    }
+}
+
+fn respawn_players(
+    mut commands: Commands,
+    mut players: Query<(
+        Entity,
+        &mut Respawning,
+        &SpawnPoint,
+        &mut Transform,
+        &mut Velocity,
+        &mut RigidBody,
+        &mut Visibility,
+        &mut Health,
+    )>,
+    time: Res<FixedTime>,
+    mut respawned_events: EventWriter<PlayerRespawned>,
+) {
+    for (
+        entity,
+        mut respawning,
+        spawn_point,
+        mut transform,
+        mut velocity,
+        mut body,
+        mut visibility,
+        mut health,
+    ) in players.iter_mut()
+    {
+        respawning.time += time.period.as_secs_f32();
+        if respawning.time < Respawning::DELAY {
+            continue;
+        }
+
+        transform.translation = spawn_point.0.extend(transform.translation.z);
+        transform.rotation = Quat::IDENTITY;
+        *velocity = Velocity::zero();
+        *body = RigidBody::Dynamic;
+        *visibility = Visibility::Inherited;
+
+        health.restore();
+        // Short invulnerability, so the player is not killed again right at the spawn point
+        health.time_since_damaged = 0.0;
+
+        commands.entity(entity).remove::<Respawning>();
+        respawned_events.send(PlayerRespawned { player: entity });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::objects::health::Health;
+    use crate::core::objects::shape::PlayerShape;
+    use bevy::ecs::event::ManualEventReader;
+
+    #[test]
+    fn player_falling_out_of_boundaries_respawns() {
+        let mut app = HeadlessApp::new();
+        app.build_scene(|builder| {
+            builder.set_boundaries(-5.0, 5.0, -5.0, 5.0);
+            builder.spawn_player(Vec2::new(0.0, 1.0), 0, PlayerShape::Square);
+        });
+        app.run(2 * SECOND);
+
+        let entity = app.get_player_entity(0);
+        assert!(app.app.world.get::<Respawning>(entity).is_some());
+
+        // Without a floor the player keeps falling after the respawn, so check the moment of respawn
+        let mut respawned_events = ManualEventReader::<PlayerRespawned>::default();
+        let mut respawned = false;
+        for _ in 0..2 * SECOND {
+            app.run(1);
+            let events = app.app.world.resource::<Events<PlayerRespawned>>();
+            if respawned_events
+                .iter(events)
+                .any(|event| event.player == entity)
+            {
+                respawned = true;
+                break;
+            }
+        }
+
+        assert!(respawned);
+        assert!(app.app.world.get::<Respawning>(entity).is_none());
+        let health = app.app.world.get::<Health>(entity).unwrap();
+        assert_eq!(health.current, health.max);
+    }
+}
@@ -1,20 +1,27 @@
 use crate::core::materials::player_material::PlayerMaterial;
+use crate::core::objects::player::{spawn_player_sides, Player};
+use crate::states::GameWorldState;
 use bevy::prelude::shape;
 use bevy::prelude::*;
 use bevy::sprite::Mesh2dHandle;
 use bevy::utils::HashMap;
 use bevy_rapier2d::prelude::Collider;
+use serde::Deserialize;
+use std::f32::consts::PI;
 
 pub struct ShapePlugin;
 
 impl Plugin for ShapePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PlayerShapeVisualBundleCache>();
-        //app.add_startup_system(init_cache.in_base_set(StartupSet::PreStartup));
+        app.add_systems(
+            (shift_player_shapes.run_if(in_state(GameWorldState::GameWorld)),)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
 pub enum PlayerShape {
     #[default]
     Square,
@@ -22,7 +29,7 @@ pub enum PlayerShape {
     Hexagon,
 }
 
-pub const MAX_SIDES: usize = 4;
+pub const MAX_SIDES: usize = 6;
 
 impl PlayerShape {
     pub fn get_default_material(&self) -> ColorMaterial {
@@ -33,16 +40,60 @@ impl PlayerShape {
         }
     }
 
-    pub fn get_default_collider(&self) -> Collider {
+    /// Returns `None` if the vertices of the shape do not make a convex polygon.
+    pub fn get_collider(&self) -> Option<Collider> {
         match *self {
-            _ => Collider::round_cuboid(0.4, 0.4, 0.1),
+            PlayerShape::Square => Some(Collider::round_cuboid(0.4, 0.4, 0.075)),
+            _ => Collider::round_convex_polyline(self.get_vertices(0.4), 0.075),
         }
     }
 
+    /// Square collider stands in for shapes without one.
+    pub fn get_default_collider(&self) -> Collider {
+        self.get_collider()
+            .unwrap_or_else(|| Collider::round_cuboid(0.4, 0.4, 0.075))
+    }
+
+    // Shader draws the body in the center half of the quad, the rest is for side addons
     pub fn get_default_mesh(&self) -> Mesh {
-        match *self {
-            _ => shape::Quad::new(Vec2::new(1.0, 1.0)).into(),
-        }
+        shape::Quad::new(Vec2::new(2.0, 2.0)).into()
+    }
+
+    /// Angle of the outward normal of the side, side 0 looks down and others go counter-clockwise.
+    pub fn get_side_angle(&self, side: usize) -> f32 {
+        -PI * 0.5 + self.get_snap_angle() * side as f32
+    }
+
+    /// Angle between two neighbouring sides.
+    pub fn get_snap_angle(&self) -> f32 {
+        2.0 * PI / self.number_of_sides() as f32
+    }
+
+    /// Length of each side of the shape with the distance from center to sides equal to `apothem`.
+    pub fn get_side_length(&self, apothem: f32) -> f32 {
+        2.0 * apothem * (PI / self.number_of_sides() as f32).tan()
+    }
+
+    pub fn get_side_directions(&self) -> Vec<Vec2> {
+        (0..self.number_of_sides() as usize)
+            .map(|i| Vec2::from_angle(self.get_side_angle(i)))
+            .collect()
+    }
+
+    pub fn get_side_centers(&self) -> Vec<Vec2> {
+        self.get_side_directions()
+            .into_iter()
+            .map(|d| d * 0.5)
+            .collect()
+    }
+
+    /// Vertex `i` is between sides `i` and `i + 1`.
+    pub fn get_vertices(&self, apothem: f32) -> Vec<Vec2> {
+        let half_angle = PI / self.number_of_sides() as f32;
+        let radius = apothem / half_angle.cos();
+        (0..self.number_of_sides() as usize)
+            .map(|i| Vec2::from_angle(self.get_side_angle(i) + half_angle) * radius)
+            .collect()
     }
 
     pub fn number_of_sides(&self) -> u32 {
@@ -53,6 +104,22 @@ impl PlayerShape {
         }
     }
 
+    /// Material shared by all players of this shape, clone it before changing side effects.
+    pub fn get_default_player_material(&self, asset_server: &AssetServer) -> PlayerMaterial {
+        PlayerMaterial {
+            color: match *self {
+                PlayerShape::Square => Color::WHITE,
+                _ => self.get_default_material().color,
+            },
+            effect_index: [0; MAX_SIDES],
+            number_of_sides: self.number_of_sides(),
+            texture: self.get_texture_file().map(|file| asset_server.load(file)),
+            emissive: None,
+            overlay: None,
+            player_effect_texture: asset_server.load("images/effect.png"),
+        }
+    }
+
     pub fn get_texture_file(&self) -> Option<&'static str> {
         match *self {
             PlayerShape::Square => Some("images/square.png"),
@@ -83,20 +150,78 @@ pub struct PlayerShapeVisualBundleCache {
     pub cache: HashMap<PlayerShape, PlayerShapeVisualBundle>,
 }
 
-/*fn init_cache(mut psv: ResMut<PlayerShapeVisualBundleCache>, mut materials: ResMut<Assets<ColorMaterial>>, mut meshes: ResMut<Assets<Mesh>>,  asset_server: Res<AssetServer>) {
-    for shape in PlayerShape::list() {
-        psv.cache.insert(
-            shape,
-            PlayerShapeVisualBundle {
+impl PlayerShapeVisualBundleCache {
+    /// Material of the returned bundle is a template, every player should use its own copy.
+    pub fn get_or_insert(
+        &mut self,
+        shape: PlayerShape,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<PlayerMaterial>,
+        asset_server: &AssetServer,
+    ) -> PlayerShapeVisualBundle {
+        self.cache
+            .entry(shape)
+            .or_insert_with(|| PlayerShapeVisualBundle {
+                mesh: meshes.add(shape.get_default_mesh()).into(),
+                material: materials.add(shape.get_default_player_material(asset_server)),
                 collider: shape.get_default_collider(),
-                sprite: Sprite {
-                    custom_size: Some(Vec2::new(1.0, 1.0)),
-                    ..default()
-                },
-                texture: shape
-                    .get_texture_file()
-                    .map_or(Handle::default(), |v| asset_server.load(v)),
-            },
-        );
+            })
+            .clone()
     }
-}*/
+}
+
+/// Player will change its shape during the next update.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ShapeShift {
+    pub shape: PlayerShape,
+}
+
+fn shift_player_shapes(
+    mut commands: Commands,
+    mut players: Query<(Entity, &ShapeShift, &mut Player, &Handle<PlayerMaterial>)>,
+    mut psv: ResMut<PlayerShapeVisualBundleCache>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<PlayerMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, shift, mut player, handle) in players.iter_mut() {
+        commands.entity(entity).remove::<ShapeShift>();
+
+        if player.shape == shift.shape {
+            continue;
+        }
+
+        let visual = psv.get_or_insert(shift.shape, &mut meshes, &mut materials, &asset_server);
+
+        for side in player.side_entities.iter().flatten() {
+            commands.entity(*side).despawn_recursive();
+        }
+
+        player.effects = player.remap_effects(shift.shape);
+        player.shape = shift.shape;
+        player.side_entities = spawn_player_sides(&mut commands, entity, shift.shape);
+
+        // Player keeps its previous body rather than getting the fallback collider of the cache
+        match shift.shape.get_collider() {
+            Some(collider) => {
+                player.small_collider = collider.clone();
+                commands.entity(entity).insert(collider);
+            }
+            None => warn!(
+                "Player {} keeps its collider, {:?} has none",
+                player.id, shift.shape
+            ),
+        }
+
+        // Keep effects in the player's own material, take the rest from the template
+        if let Some(template) = materials.get(&visual.material).cloned() {
+            if let Some(material) = materials.get_mut(handle) {
+                material.color = template.color;
+                material.texture = template.texture;
+                material.number_of_sides = template.number_of_sides;
+            }
+        }
+
+        commands.entity(entity).insert(visual.mesh);
+    }
+}
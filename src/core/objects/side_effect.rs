@@ -1,4 +1,6 @@
-#[derive(Default, Debug, Clone, Copy, PartialEq, Hash)]
+use serde::Deserialize;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Hash, Deserialize)]
 pub enum SideEffect {
     #[default]
     None,
@@ -0,0 +1,419 @@
+use crate::core::deterministic::FixedEventApp;
+use crate::core::materials::bonus_material::BonusMaterial;
+use crate::core::materials::monster_material::MonsterMaterial;
+use crate::core::materials::player_material::PlayerMaterial;
+use crate::core::objects::door::DoorChannels;
+use crate::core::objects::health::Health;
+use crate::core::objects::monster::{Monster, MonsterDying, MonsterKind};
+use crate::core::objects::player::Player;
+use crate::core::objects::shape::PlayerShapeVisualBundleCache;
+use crate::core::scene_builder::scene_boundaries::SceneBoundaries;
+use crate::core::scene_builder::SceneBuilder;
+use crate::states::GameWorldState;
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+use serde::Deserialize;
+
+pub struct SpawnerPlugin;
+
+impl Plugin for SpawnerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_fixed_event::<MonsterSpawned>();
+        app.add_fixed_event::<EncounterStarted>();
+        app.add_fixed_event::<EncounterCompleted>();
+
+        app.add_systems(
+            (
+                update_encounters.run_if(in_state(GameWorldState::GameWorld)),
+                update_spawners
+                    .after(update_encounters)
+                    .run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+
+        app.add_system(
+            draw_encounters
+                .run_if(resource_exists::<DebugLines>())
+                .run_if(in_state(GameWorldState::GameWorld)),
+        );
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum SpawnerTrigger {
+    // Spawns a monster every `interval` seconds
+    Timer { interval: f32 },
+    // Spawns `count` monsters once a player comes close enough
+    PlayerEnter { half_size: Vec2, count: u32 },
+    // Spawns only monsters requested by encounter waves
+    Waves,
+}
+
+/// Point that monsters appear at.
+#[derive(Component, Clone, Debug)]
+pub struct Spawner {
+    // Encounter waves refer to spawners by this id, spawners without it are never in a wave
+    pub id: Option<u32>,
+    pub kind: MonsterKind,
+    pub patrol: bool,
+    pub trigger: SpawnerTrigger,
+    // New monsters wait while this many monsters of the spawner are alive
+    pub max_alive: usize,
+    pub alive: Vec<Entity>,
+    // Monsters waiting to be spawned
+    pub pending: u32,
+    pub time: f32,
+    pub triggered: bool,
+}
+
+impl Spawner {
+    pub fn new(id: Option<u32>, kind: MonsterKind, trigger: SpawnerTrigger) -> Self {
+        Spawner {
+            id,
+            kind,
+            patrol: false,
+            trigger,
+            max_alive: 3,
+            alive: vec![],
+            pending: 0,
+            time: 0.0,
+            triggered: false,
+        }
+    }
+
+    pub fn can_spawn(&self) -> bool {
+        self.pending > 0 && self.alive.len() < self.max_alive
+    }
+}
+
+/// Monster appeared at a spawner.
+#[derive(Clone, Debug)]
+pub struct MonsterSpawned {
+    pub monster: Entity,
+    pub spawner: Entity,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Wave {
+    pub spawns: Vec<WaveSpawn>,
+    // Pause before the wave after the previous one is cleared
+    #[serde(default)]
+    pub delay: f32,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct WaveSpawn {
+    pub spawner: u32,
+    pub count: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EncounterState {
+    // Waits for a player to enter the arena, doors are open
+    #[default]
+    Waiting,
+    // Doors are locked, the current wave starts after its delay
+    Delay,
+    // Monsters of the current wave are alive or still to be spawned
+    Fighting,
+    // All waves are cleared and doors are open again
+    Cleared,
+}
+
+/// Arena that locks its doors and sends waves of monsters once a player enters it.
+#[derive(Component, Clone, Debug)]
+pub struct Encounter {
+    // Level logic tells encounters apart by this id
+    pub id: u32,
+    // Players inside start the encounter
+    pub area: Rect,
+    // Doors of this channel are closed until all waves are cleared
+    pub door_channel: u32,
+    pub waves: Vec<Wave>,
+    pub state: EncounterState,
+    pub wave: usize,
+    // Time spent in the current state
+    pub time: f32,
+    // Monsters of the current wave that are still alive
+    pub alive: Vec<Entity>,
+}
+
+impl Encounter {
+    pub fn new(id: u32, area: Rect, door_channel: u32, waves: Vec<Wave>) -> Self {
+        Encounter {
+            id,
+            area,
+            door_channel,
+            waves,
+            state: EncounterState::Waiting,
+            wave: 0,
+            time: 0.0,
+            alive: vec![],
+        }
+    }
+
+    fn is_wave_spawner(&self, spawner: &Spawner) -> bool {
+        self.waves
+            .get(self.wave)
+            .is_some_and(|wave| wave.spawns.iter().any(|s| spawner.id == Some(s.spawner)))
+    }
+}
+
+/// Player entered the arena and its doors got locked.
+#[derive(Clone, Debug)]
+pub struct EncounterStarted {
+    pub encounter: Entity,
+    pub id: u32,
+}
+
+/// All waves of the encounter are cleared and its doors are open.
+#[derive(Clone, Debug)]
+pub struct EncounterCompleted {
+    pub encounter: Entity,
+    pub id: u32,
+}
+
+fn update_encounters(
+    mut encounters: Query<(Entity, &mut Encounter)>,
+    mut spawners: Query<(Entity, &mut Spawner)>,
+    monsters: Query<(), (With<Monster>, Without<MonsterDying>)>,
+    players: Query<(&Transform, &Health), With<Player>>,
+    mut channels: ResMut<DoorChannels>,
+    mut spawned_events: EventReader<MonsterSpawned>,
+    mut started_events: EventWriter<EncounterStarted>,
+    mut completed_events: EventWriter<EncounterCompleted>,
+    time: Res<FixedTime>,
+) {
+    let spawned: Vec<MonsterSpawned> = spawned_events.iter().cloned().collect();
+
+    for (entity, mut encounter) in encounters.iter_mut() {
+        encounter.time += time.period.as_secs_f32();
+
+        // Doors are only touched on state changes, so other logic may drive the channel too
+        if encounter.is_added() && encounter.state == EncounterState::Waiting {
+            channels.set(encounter.door_channel, true);
+        }
+
+        match encounter.state {
+            EncounterState::Waiting => {
+                let entered = players.iter().any(|(transform, health)| {
+                    !health.is_dead() && encounter.area.contains(transform.translation.truncate())
+                });
+
+                if entered {
+                    channels.set(encounter.door_channel, false);
+                    encounter.state = EncounterState::Delay;
+                    encounter.time = 0.0;
+                    started_events.send(EncounterStarted {
+                        encounter: entity,
+                        id: encounter.id,
+                    });
+                }
+            }
+            EncounterState::Delay => {
+                let delay = encounter.waves.get(encounter.wave).map_or(0.0, |w| w.delay);
+                if encounter.time < delay {
+                    continue;
+                }
+
+                if let Some(wave) = encounter.waves.get(encounter.wave) {
+                    for spawn in &wave.spawns {
+                        for (_, mut spawner) in spawners.iter_mut() {
+                            if spawner.id == Some(spawn.spawner) {
+                                spawner.pending += spawn.count;
+                            }
+                        }
+                    }
+                }
+
+                encounter.state = EncounterState::Fighting;
+                encounter.time = 0.0;
+            }
+            EncounterState::Fighting => {
+                for event in &spawned {
+                    let from_wave = spawners
+                        .get(event.spawner)
+                        .is_ok_and(|(_, spawner)| encounter.is_wave_spawner(spawner));
+                    if from_wave {
+                        encounter.alive.push(event.monster);
+                    }
+                }
+
+                encounter
+                    .alive
+                    .retain(|monster| monsters.contains(*monster));
+
+                let spawning = spawners
+                    .iter()
+                    .any(|(_, spawner)| encounter.is_wave_spawner(spawner) && spawner.pending > 0);
+
+                if spawning || !encounter.alive.is_empty() {
+                    continue;
+                }
+
+                encounter.wave += 1;
+                encounter.time = 0.0;
+
+                if encounter.wave < encounter.waves.len() {
+                    encounter.state = EncounterState::Delay;
+                } else {
+                    encounter.state = EncounterState::Cleared;
+                    channels.set(encounter.door_channel, true);
+                    completed_events.send(EncounterCompleted {
+                        encounter: entity,
+                        id: encounter.id,
+                    });
+                }
+            }
+            EncounterState::Cleared => {}
+        }
+    }
+}
+
+fn update_spawners(
+    commands: Commands,
+    asset_server: Res<AssetServer>,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<ColorMaterial>>,
+    player_materials: ResMut<Assets<PlayerMaterial>>,
+    bonus_materials: ResMut<Assets<BonusMaterial>>,
+    monster_materials: ResMut<Assets<MonsterMaterial>>,
+    boundaries: ResMut<SceneBoundaries>,
+    psv: ResMut<PlayerShapeVisualBundleCache>,
+    mut spawners: Query<(Entity, &mut Spawner, &Transform)>,
+    monsters: Query<(), (With<Monster>, Without<MonsterDying>)>,
+    players: Query<(&Transform, &Health), With<Player>>,
+    mut spawned_events: EventWriter<MonsterSpawned>,
+    time: Res<FixedTime>,
+) {
+    for (_, mut spawner, transform) in spawners.iter_mut() {
+        let position = transform.translation.truncate();
+
+        spawner.alive.retain(|monster| monsters.contains(*monster));
+        spawner.time += time.period.as_secs_f32();
+
+        match spawner.trigger.clone() {
+            SpawnerTrigger::Timer { interval } => {
+                if spawner.time >= interval && spawner.pending == 0 {
+                    spawner.pending = 1;
+                }
+            }
+            SpawnerTrigger::PlayerEnter { half_size, count } => {
+                let area = Rect::from_center_half_size(position, half_size);
+                let entered = players.iter().any(|(transform, health)| {
+                    !health.is_dead() && area.contains(transform.translation.truncate())
+                });
+                if entered && !spawner.triggered {
+                    spawner.triggered = true;
+                    spawner.pending += count;
+                }
+            }
+            SpawnerTrigger::Waves => {}
+        }
+    }
+
+    if !spawners.iter().any(|(_, spawner, _)| spawner.can_spawn()) {
+        return;
+    }
+
+    let mut scene_builder = SceneBuilder::new(
+        commands,
+        asset_server,
+        meshes,
+        materials,
+        player_materials,
+        bonus_materials,
+        monster_materials,
+        boundaries,
+        psv,
+    );
+
+    for (entity, mut spawner, transform) in spawners.iter_mut() {
+        // One monster per step, so they do not appear inside each other
+        if !spawner.can_spawn() {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let monster = scene_builder.spawn_monster(position, spawner.kind, spawner.patrol);
+        spawner.alive.push(monster);
+        spawner.pending -= 1;
+        spawner.time = 0.0;
+        spawned_events.send(MonsterSpawned {
+            monster,
+            spawner: entity,
+        });
+    }
+}
+
+fn draw_encounters(encounters: Query<&Encounter>, mut lines: ResMut<DebugLines>) {
+    for encounter in &encounters {
+        let color = match encounter.state {
+            EncounterState::Waiting => Color::GRAY,
+            EncounterState::Delay | EncounterState::Fighting => Color::RED,
+            EncounterState::Cleared => Color::GREEN,
+        };
+
+        let Rect { min, max } = encounter.area;
+        let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+        for i in 0..corners.len() {
+            let from = corners[i];
+            let to = corners[(i + 1) % corners.len()];
+            lines.line_colored(from.extend(1.0), to.extend(1.0), 0.0, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::objects::door::DoorChannels;
+    use crate::core::objects::health::Health;
+    use crate::core::objects::monster::Monster;
+    use crate::core::objects::shape::PlayerShape;
+
+    #[test]
+    fn encounter_locks_doors_until_waves_are_cleared() {
+        let mut app = HeadlessApp::new();
+        let mut encounter = None;
+        app.build_scene(|builder| {
+            builder.spawn_wall_from_to(Vec2::new(-10.0, -1.0), Vec2::new(10.0, -2.0));
+            builder.spawn_player(Vec2::new(0.0, -0.5), 0, PlayerShape::Square);
+
+            let spawner = Spawner::new(Some(1), MonsterKind::Walker, SpawnerTrigger::Waves);
+            builder.spawn_spawner(Vec2::new(3.0, -0.5), spawner);
+
+            let wave = Wave {
+                spawns: vec![WaveSpawn {
+                    spawner: 1,
+                    count: 2,
+                }],
+                delay: 0.0,
+            };
+            let area = Rect::new(-5.0, -1.0, 5.0, 3.0);
+            encounter = Some(builder.spawn_encounter(Encounter::new(0, area, 7, vec![wave])));
+        });
+        app.run(SECOND / 2);
+
+        let encounter = encounter.unwrap();
+        assert!(!app.app.world.resource::<DoorChannels>().is_open(7));
+        assert_eq!(
+            app.app.world.get::<Encounter>(encounter).unwrap().state,
+            EncounterState::Fighting
+        );
+
+        let mut monsters = app.app.world.query_filtered::<&mut Health, With<Monster>>();
+        for mut health in monsters.iter_mut(&mut app.app.world) {
+            health.current = 0.0;
+        }
+        app.run(SECOND / 2);
+
+        assert!(app.app.world.resource::<DoorChannels>().is_open(7));
+        assert_eq!(
+            app.app.world.get::<Encounter>(encounter).unwrap().state,
+            EncounterState::Cleared
+        );
+    }
+}
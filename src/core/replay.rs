@@ -0,0 +1,202 @@
+use crate::core::deterministic::{DeterministicSettings, StepPerUpdate};
+use crate::core::input::{ActionState, InputSet};
+use crate::core::objects::player::Player;
+use crate::states::GameWorldState;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                record_actions
+                    .after(InputSet)
+                    .run_if(resource_exists::<ReplayRecorder>())
+                    .run_if(in_state(GameWorldState::GameWorld)),
+                play_actions
+                    .in_set(InputSet)
+                    .run_if(resource_exists::<ReplayPlayback>())
+                    .run_if(in_state(GameWorldState::GameWorld)),
+            )
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+
+        app.add_system(unlock_frame_rate.run_if(resource_added::<ReplayPlayback>()));
+
+        app.add_system(
+            save_replay
+                .in_base_set(CoreSet::Last)
+                .run_if(resource_exists::<ReplayRecorder>()),
+        );
+    }
+}
+
+/// Pressed actions of every player for every step, requires the deterministic mode to replay.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Replay {
+    pub timestep: f32,
+    pub substeps: usize,
+    pub seed: u64,
+    // Run-length encoded `(steps, pressed bits)` by `Player::id`
+    pub players: BTreeMap<u32, Vec<(u32, u8)>>,
+}
+
+impl Replay {
+    pub fn new(settings: &DeterministicSettings) -> Self {
+        Replay {
+            timestep: settings.timestep,
+            substeps: settings.substeps,
+            seed: settings.seed,
+            players: BTreeMap::new(),
+        }
+    }
+
+    pub fn get_settings(&self) -> DeterministicSettings {
+        DeterministicSettings {
+            timestep: self.timestep,
+            substeps: self.substeps,
+            seed: self.seed,
+        }
+    }
+
+    pub fn push(&mut self, id: u32, step: u32, bits: u8) {
+        let runs = self.players.entry(id).or_default();
+
+        // Player appeared later than the first step
+        let recorded: u32 = runs.iter().map(|(steps, _)| steps).sum();
+        if recorded < step {
+            runs.push((step - recorded, 0));
+        }
+
+        match runs.last_mut() {
+            Some((steps, last_bits)) if *last_bits == bits => *steps += 1,
+            _ => runs.push((1, bits)),
+        }
+    }
+
+    pub fn get(&self, id: u32, step: u32) -> Option<u8> {
+        let mut start = 0;
+        for &(steps, bits) in self.players.get(&id)? {
+            if step < start + steps {
+                return Some(bits);
+            }
+            start += steps;
+        }
+        None
+    }
+
+    pub fn len(&self) -> u32 {
+        self.players
+            .values()
+            .map(|runs| runs.iter().map(|(steps, _)| steps).sum())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        ron::de::from_bytes(&bytes).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let text = ron::to_string(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
+
+/// Records actions of all players and saves them to `path` on exit.
+#[derive(Resource, Debug, Clone)]
+pub struct ReplayRecorder {
+    pub path: String,
+    pub replay: Replay,
+    pub step: u32,
+}
+
+/// Feeds recorded actions to players instead of the live input.
+#[derive(Resource, Debug, Clone)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub step: u32,
+    // One step per frame without waiting for vsync, so the replay runs as fast as possible
+    pub fast: bool,
+    // All recorded steps were played, players get no actions anymore
+    pub finished: bool,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay, fast: bool) -> Self {
+        ReplayPlayback {
+            replay,
+            step: 0,
+            fast,
+            finished: false,
+        }
+    }
+}
+
+fn record_actions(players: Query<(&Player, &ActionState)>, mut recorder: ResMut<ReplayRecorder>) {
+    let recorder: &mut ReplayRecorder = &mut recorder;
+    for (player, actions) in &players {
+        recorder
+            .replay
+            .push(player.id, recorder.step, actions.get_pressed_bits());
+    }
+    recorder.step += 1;
+}
+
+fn play_actions(
+    mut players: Query<(&Player, &mut ActionState)>,
+    mut playback: ResMut<ReplayPlayback>,
+) {
+    if playback.finished {
+        return;
+    }
+
+    if playback.step >= playback.replay.len() {
+        info!("Replay finished after {} steps", playback.step);
+        playback.finished = true;
+
+        for (_, mut actions) in players.iter_mut() {
+            actions.set_pressed_bits(0);
+        }
+        return;
+    }
+
+    for (player, mut actions) in players.iter_mut() {
+        let bits = playback.replay.get(player.id, playback.step).unwrap_or(0);
+        actions.set_pressed_bits(bits);
+    }
+    playback.step += 1;
+}
+
+fn unlock_frame_rate(
+    mut commands: Commands,
+    playback: Res<ReplayPlayback>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !playback.fast {
+        return;
+    }
+
+    commands.insert_resource(StepPerUpdate);
+
+    for mut window in windows.iter_mut() {
+        window.present_mode = PresentMode::AutoNoVsync;
+    }
+}
+
+fn save_replay(mut exit_events: EventReader<AppExit>, recorder: Res<ReplayRecorder>) {
+    if exit_events.iter().next().is_none() {
+        return;
+    }
+
+    match recorder.replay.save(&recorder.path) {
+        Ok(()) => info!("Replay saved to {}", recorder.path),
+        Err(e) => error!("Failed to save replay to {}: {}", recorder.path, e),
+    }
+}
@@ -1,40 +1,81 @@
-use crate::core::objects::bonus::Bonus;
+use crate::core::objects::bonus::{Bonus, ShapeBonus};
 
 use crate::core::materials::bonus_material::BonusMaterial;
-use crate::core::objects::collision_groups::BONUS_CG;
+use crate::core::objects::collision_groups::{BONUS_CG, DARK_BONUS_LIT_CG, DARK_CG};
+use crate::core::objects::flashlight::Dark;
+use crate::core::objects::shape::PlayerShape;
 use crate::core::objects::side_effect::SideEffect;
 use crate::core::scene_builder::SceneBuilder;
 use bevy::prelude::*;
-use bevy::sprite::Mesh2dHandle;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
 use bevy_rapier2d::prelude::{ActiveEvents, Collider, RigidBody, Sensor};
 
 impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
-    pub fn spawn_effect_bonus(&mut self, position: Vec2, effect: SideEffect) {
+    pub fn spawn_effect_bonus(&mut self, position: Vec2, effect: SideEffect) -> Entity {
         let bonus_material = BonusMaterial {
             effect_index: effect.to_index(),
             texture: Some(self.asset_server.load("images/bonus.png")),
         };
 
-        self.commands.spawn((
-            Bonus {
-                effect: Some(effect),
-            },
-            VisibilityBundle::default(),
-            TransformBundle::from_transform(Transform::from_xyz(
-                position.x,
-                position.y,
-                SceneBuilder::BONUS_DEPTH,
-            )),
-            self.bonus_materials.add(bonus_material),
-            Mesh2dHandle(
-                self.meshes
-                    .add(shape::Quad::new(Vec2::new(0.5, 0.5)).into()),
-            ),
-            RigidBody::Fixed,
-            Sensor,
-            ActiveEvents::COLLISION_EVENTS,
-            Collider::ball(0.25),
-            BONUS_CG,
+        self.commands
+            .spawn((
+                Bonus {
+                    effect: Some(effect),
+                },
+                VisibilityBundle::default(),
+                TransformBundle::from_transform(Transform::from_xyz(
+                    position.x,
+                    position.y,
+                    SceneBuilder::BONUS_DEPTH,
+                )),
+                self.bonus_materials.add(bonus_material),
+                Mesh2dHandle(
+                    self.meshes
+                        .add(shape::Quad::new(Vec2::new(0.5, 0.5)).into()),
+                ),
+                RigidBody::Fixed,
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                Collider::ball(0.25),
+                BONUS_CG,
+            ))
+            .id()
+    }
+
+    /// Bonus that can be seen and picked up only while some flashlight lights it.
+    pub fn spawn_dark_effect_bonus(&mut self, position: Vec2, effect: SideEffect) -> Entity {
+        let entity = self.spawn_effect_bonus(position, effect);
+        self.commands.entity(entity).insert((
+            Dark::new(DARK_BONUS_LIT_CG),
+            DARK_CG,
+            Visibility::Hidden,
         ));
+        entity
+    }
+
+    /// Bonus that turns the player into `shape`, side effects are moved to the nearest new sides.
+    pub fn spawn_shape_bonus(&mut self, position: Vec2, shape: PlayerShape) -> Entity {
+        self.commands
+            .spawn((
+                ShapeBonus { shape: Some(shape) },
+                MaterialMesh2dBundle {
+                    mesh: self
+                        .meshes
+                        .add(
+                            shape::RegularPolygon::new(0.25, shape.number_of_sides() as usize)
+                                .into(),
+                        )
+                        .into(),
+                    material: self.materials.add(shape.get_default_material()),
+                    transform: Transform::from_xyz(position.x, position.y, Self::BONUS_DEPTH),
+                    ..default()
+                },
+                RigidBody::Fixed,
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                Collider::ball(0.25),
+                BONUS_CG,
+            ))
+            .id()
     }
 }
@@ -0,0 +1,36 @@
+use crate::core::objects::collision_groups::WALL_CG;
+use crate::core::objects::door::Door;
+use crate::core::scene_builder::wall::Wall;
+use crate::core::scene_builder::SceneBuilder;
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+use bevy_rapier2d::prelude::*;
+
+impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
+    pub fn spawn_door_from_to(&mut self, from: Vec2, to: Vec2, channel: u32) -> Entity {
+        let size = from.max(to) - from.min(to);
+        let translation = (from + to) * 0.5;
+        self.commands
+            .spawn((
+                Wall::default(),
+                Door {
+                    channel,
+                    open: false,
+                },
+                Collider::cuboid(size.x * 0.5, size.y * 0.5),
+                RigidBody::Fixed,
+                MaterialMesh2dBundle {
+                    mesh: self.meshes.add(shape::Quad::new(size).into()).into(),
+                    material: self.materials.add(Color::rgb(0.25, 0.12, 0.1).into()),
+                    transform: Transform::from_translation(Vec3::new(
+                        translation.x,
+                        translation.y,
+                        Self::WALL_DEPTH,
+                    )),
+                    ..default()
+                },
+                WALL_CG,
+            ))
+            .id()
+    }
+}
@@ -0,0 +1,34 @@
+use crate::core::objects::gravity_field::{GravityField, GravityFieldKind};
+use crate::core::scene_builder::SceneBuilder;
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+
+impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
+    pub fn spawn_gravity_field(
+        &mut self,
+        position: Vec2,
+        kind: GravityFieldKind,
+        strength: f32,
+    ) -> Entity {
+        let mesh: Mesh = match kind {
+            GravityFieldKind::Directional { half_size, .. } => {
+                shape::Quad::new(half_size * 2.0).into()
+            }
+            GravityFieldKind::Radial { radius } => shape::Circle::new(radius).into(),
+        };
+
+        self.commands
+            .spawn((
+                GravityField { kind, strength },
+                MaterialMesh2dBundle {
+                    mesh: self.meshes.add(mesh).into(),
+                    material: self
+                        .materials
+                        .add(ColorMaterial::from(Color::rgba(0.4, 0.2, 0.8, 0.15))),
+                    transform: Transform::from_xyz(position.x, position.y, Self::FIELD_DEPTH),
+                    ..default()
+                },
+            ))
+            .id()
+    }
+}
@@ -0,0 +1,41 @@
+use crate::core::objects::collision_groups::BONUS_CG;
+use crate::core::objects::gravity_switch::{GravityChange, GravitySwitch};
+use crate::core::scene_builder::SceneBuilder;
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+use bevy_rapier2d::prelude::{ActiveEvents, Collider, RigidBody, Sensor};
+use std::f32::consts::PI;
+
+impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
+    /// Triangle pointing to the new gravity direction, or a circle for relative changes.
+    pub fn spawn_gravity_switch(&mut self, position: Vec2, change: GravityChange) -> Entity {
+        let (mesh, rotation) = match change {
+            GravityChange::Set(direction) => (
+                shape::RegularPolygon::new(0.3, 3).into(),
+                // Triangle points up by default
+                Quat::from_rotation_z(PI + direction.get_index() as f32 * PI * 0.5),
+            ),
+            _ => (shape::Circle::new(0.25).into(), Quat::IDENTITY),
+        };
+
+        self.commands
+            .spawn((
+                GravitySwitch { change },
+                MaterialMesh2dBundle {
+                    mesh: self.meshes.add(mesh).into(),
+                    material: self
+                        .materials
+                        .add(ColorMaterial::from(Color::rgb(0.6, 0.3, 1.5))),
+                    transform: Transform::from_xyz(position.x, position.y, Self::BONUS_DEPTH)
+                        .with_rotation(rotation),
+                    ..default()
+                },
+                RigidBody::Fixed,
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                Collider::ball(0.3),
+                BONUS_CG,
+            ))
+            .id()
+    }
+}
@@ -0,0 +1,46 @@
+use crate::core::objects::collision_groups::{LAVA_CG, WALL_CG};
+use crate::core::objects::hazard::{Hazard, HazardKind};
+use crate::core::scene_builder::SceneBuilder;
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+use bevy_rapier2d::prelude::*;
+
+impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
+    pub fn spawn_hazard_from_to(&mut self, from: Vec2, to: Vec2, kind: HazardKind) -> Entity {
+        let size = from.max(to) - from.min(to);
+        let translation = (from + to) * 0.5;
+
+        let (damage, color) = match kind {
+            HazardKind::Spikes => (1.0, Color::rgb(0.5, 0.5, 0.55)),
+            HazardKind::Lava => (f32::INFINITY, Color::rgb(3.0, 0.8, 0.1)),
+        };
+
+        let mut hazard = self.commands.spawn((
+            Hazard { kind, damage },
+            Collider::cuboid(size.x * 0.5, size.y * 0.5),
+            RigidBody::Fixed,
+            MaterialMesh2dBundle {
+                mesh: self.meshes.add(shape::Quad::new(size).into()).into(),
+                material: self.materials.add(color.into()),
+                transform: Transform::from_translation(Vec3::new(
+                    translation.x,
+                    translation.y,
+                    Self::WALL_DEPTH,
+                )),
+                ..default()
+            },
+        ));
+
+        match kind {
+            HazardKind::Spikes => {
+                hazard.insert(WALL_CG);
+            }
+            // Bodies sink into lava instead of standing on it
+            HazardKind::Lava => {
+                hazard.insert((Sensor, ActiveEvents::COLLISION_EVENTS, LAVA_CG));
+            }
+        }
+
+        hazard.id()
+    }
+}
@@ -0,0 +1,33 @@
+use crate::core::objects::collision_groups::WALL_CG;
+use crate::core::objects::laser::LaserReceiver;
+use crate::core::scene_builder::wall::Wall;
+use crate::core::scene_builder::SceneBuilder;
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+use bevy_rapier2d::prelude::*;
+
+impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
+    /// Solid block that opens doors of `channel` while a laser hits it.
+    pub fn spawn_laser_receiver(&mut self, position: Vec2, channel: u32) -> Entity {
+        let size = Vec2::new(0.5, 0.5);
+        self.commands
+            .spawn((
+                Wall::default(),
+                LaserReceiver::new(channel),
+                Collider::cuboid(size.x * 0.5, size.y * 0.5),
+                RigidBody::Fixed,
+                MaterialMesh2dBundle {
+                    mesh: self.meshes.add(shape::Quad::new(size).into()).into(),
+                    material: self.materials.add(LaserReceiver::get_color(false).into()),
+                    transform: Transform::from_translation(Vec3::new(
+                        position.x,
+                        position.y,
+                        Self::WALL_DEPTH,
+                    )),
+                    ..default()
+                },
+                WALL_CG,
+            ))
+            .id()
+    }
+}
@@ -0,0 +1,121 @@
+use crate::core::level::Level;
+use crate::core::objects::spawner::{Encounter, Spawner};
+use crate::core::scene_builder::SceneBuilder;
+use bevy::prelude::*;
+
+impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
+    pub fn spawn_level(&mut self, level: &Level) {
+        if let Some(b) = level.boundaries {
+            self.set_boundaries(b.left, b.right, b.bottom, b.top);
+        }
+
+        if let Some(range) = level.view_range {
+            self.set_min_view_range(range);
+        }
+
+        if let Some(range) = level.max_view_range {
+            self.set_max_view_range(range);
+        }
+
+        for wall in &level.walls {
+            if wall.dark {
+                self.spawn_dark_wall_from_to(wall.from, wall.to);
+            } else {
+                self.spawn_wall_from_to(wall.from, wall.to);
+            }
+        }
+
+        for wall in &level.boxes {
+            self.spawn_rotated_box(wall.position, wall.size, wall.angle);
+        }
+
+        for polygon in &level.polygons {
+            self.spawn_polygon_wall(&polygon.points);
+        }
+
+        for polyline in &level.polylines {
+            self.spawn_polyline_wall(&polyline.points, polyline.thickness);
+        }
+
+        for slope in &level.slopes {
+            self.spawn_slope(slope.from, slope.to);
+        }
+
+        for hazard in &level.hazards {
+            self.spawn_hazard_from_to(hazard.from, hazard.to, hazard.kind);
+        }
+
+        for platform in &level.one_way_platforms {
+            self.spawn_one_way_platform(platform.from, platform.to);
+        }
+
+        for platform in &level.moving_platforms {
+            self.spawn_moving_platform(platform.size, platform.path.clone());
+        }
+
+        for platform in &level.rotating_platforms {
+            self.spawn_rotating_platform(platform.position, platform.size, platform.angular_speed);
+        }
+
+        for door in &level.doors {
+            self.spawn_door_from_to(door.from, door.to, door.channel);
+        }
+
+        for receiver in &level.laser_receivers {
+            self.spawn_laser_receiver(receiver.position, receiver.channel);
+        }
+
+        for field in &level.gravity_fields {
+            self.spawn_gravity_field(field.position, field.kind, field.strength);
+        }
+
+        for switch in &level.gravity_switches {
+            self.spawn_gravity_switch(switch.position, switch.change);
+        }
+
+        for bonus in &level.bonuses {
+            if bonus.dark {
+                self.spawn_dark_effect_bonus(bonus.position, bonus.effect);
+            } else {
+                self.spawn_effect_bonus(bonus.position, bonus.effect);
+            }
+        }
+
+        for bonus in &level.shape_bonuses {
+            self.spawn_shape_bonus(bonus.position, bonus.shape);
+        }
+
+        for monster in &level.monsters {
+            self.spawn_monster_with_bounds(
+                monster.position,
+                monster.kind,
+                monster.patrol,
+                monster.patrol_bounds,
+            );
+        }
+
+        for spawner in &level.spawners {
+            self.spawn_spawner(
+                spawner.position,
+                Spawner {
+                    patrol: spawner.patrol,
+                    max_alive: spawner.max_alive,
+                    ..Spawner::new(spawner.id, spawner.kind, spawner.trigger.clone())
+                },
+            );
+        }
+
+        for encounter in &level.encounters {
+            self.spawn_encounter(Encounter::new(
+                encounter.id,
+                Rect::from_corners(encounter.from, encounter.to),
+                encounter.channel,
+                encounter.waves.clone(),
+            ));
+        }
+
+        for player in &level.players {
+            self.spawn_player(player.position, player.id, player.shape);
+        }
+    }
+}
@@ -7,10 +7,18 @@ use bevy::prelude::*;
 use self::scene_boundaries::SceneBoundaries;
 
 mod bonus;
+mod door;
+mod gravity_field;
+mod gravity_switch;
+mod hazard;
+mod laser_receiver;
+mod level;
 mod monster;
+mod platform;
 mod player;
 pub mod scene_boundaries;
-mod wall;
+mod spawner;
+pub mod wall;
 
 pub struct SceneBuilder<'w, 's, 'a> {
     commands: Commands<'w, 's>,
@@ -28,6 +36,7 @@ impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
     pub const BONUS_DEPTH: f32 = 0.6;
     pub const PLAYER_DEPTH: f32 = 0.5;
     pub const WALL_DEPTH: f32 = 0.2;
+    pub const FIELD_DEPTH: f32 = 0.1;
 
     pub fn new(
         commands: Commands<'w, 's>,
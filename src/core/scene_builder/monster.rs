@@ -1,5 +1,8 @@
-use crate::core::objects::collision_groups::BONUS_CG;
-use crate::core::objects::monster::Monster;
+use crate::core::objects::gravity_field::LocalGravity;
+use crate::core::objects::health::Health;
+use crate::core::objects::monster::{Monster, MonsterKind, PatrolBounds};
+use crate::core::objects::monster_ai::MonsterAi;
+use crate::core::objects::navigation::MonsterPath;
 use crate::core::scene_builder::SceneBuilder;
 use crate::core::{
     materials::monster_material::MonsterMaterial, objects::collision_groups::MONSTER_CG,
@@ -9,48 +12,89 @@ use bevy::sprite::Mesh2dHandle;
 use bevy_rapier2d::prelude::*;
 
 impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
-    pub fn spawn_monster(&mut self, position: Vec2) {
+    pub fn spawn_monster(&mut self, position: Vec2, kind: MonsterKind, patrol: bool) -> Entity {
+        self.spawn_monster_with_bounds(position, kind, patrol, None)
+    }
+
+    pub fn spawn_monster_with_bounds(
+        &mut self,
+        position: Vec2,
+        kind: MonsterKind,
+        patrol: bool,
+        patrol_bounds: Option<PatrolBounds>,
+    ) -> Entity {
         let monster_material = MonsterMaterial {
-            monster_index: 0,
+            monster_index: kind.get_index(),
             animation_tick: 0,
             state: 0,
             texture: Some(self.asset_server.load("images/monster.png")),
         };
 
-        let points = vec![
-            Vec2::new(0.3, -0.5),
-            Vec2::new(0.44, -0.2),
-            Vec2::new(0.44, 0.2),
-            Vec2::new(0.3, 0.5),
-            Vec2::new(-0.3, 0.5),
-            Vec2::new(-0.44, 0.2),
-            Vec2::new(-0.44, -0.2),
-            Vec2::new(-0.3, -0.5),
-        ];
+        let half_size = kind.get_half_size();
+
+        let collider = match kind {
+            MonsterKind::Crawler | MonsterKind::Flyer => Collider::ball(half_size * 0.8),
+            MonsterKind::Walker
+            | MonsterKind::Jumper
+            | MonsterKind::Pusher
+            | MonsterKind::Thief => {
+                let points = vec![
+                    Vec2::new(0.3, -0.5),
+                    Vec2::new(0.44, -0.2),
+                    Vec2::new(0.44, 0.2),
+                    Vec2::new(0.3, 0.5),
+                    Vec2::new(-0.3, 0.5),
+                    Vec2::new(-0.44, 0.2),
+                    Vec2::new(-0.44, -0.2),
+                    Vec2::new(-0.3, -0.5),
+                ];
+                let scale = half_size / 0.5;
+                Collider::convex_polyline(points.into_iter().map(|p| p * scale).collect()).unwrap()
+            }
+        };
 
-        let collider = Collider::convex_polyline(points).unwrap();
+        let gravity_scale = if kind.ignores_gravity() { 0.0 } else { 1.0 };
 
-        self.commands.spawn((
-            VisibilityBundle::default(),
-            TransformBundle::from_transform(Transform::from_xyz(
-                position.x,
-                position.y,
-                SceneBuilder::BONUS_DEPTH,
-            )),
-            self.monster_materials.add(monster_material),
-            Mesh2dHandle(
-                self.meshes
-                    .add(shape::Quad::new(Vec2::new(1.0, 1.0)).into()),
-            ),
-            RigidBody::Dynamic,
-            ActiveEvents::COLLISION_EVENTS,
-            LockedAxes::ROTATION_LOCKED,
-            collider,
-            MONSTER_CG,
-            ReadMassProperties::default(),
-            ExternalImpulse::default(),
-            Velocity::default(),
-            Monster { patrol: true },
-        ));
+        self.commands
+            .spawn((
+                VisibilityBundle::default(),
+                TransformBundle::from_transform(Transform::from_xyz(
+                    position.x,
+                    position.y,
+                    SceneBuilder::BONUS_DEPTH,
+                )),
+                self.monster_materials.add(monster_material),
+                Mesh2dHandle(
+                    self.meshes
+                        .add(shape::Quad::new(Vec2::splat(half_size * 2.0)).into()),
+                ),
+                RigidBody::Dynamic,
+                ActiveEvents::COLLISION_EVENTS,
+                LockedAxes::ROTATION_LOCKED,
+                (
+                    collider,
+                    ColliderMassProperties::Density(kind.get_density()),
+                ),
+                MONSTER_CG,
+                ReadMassProperties::default(),
+                ExternalImpulse::default(),
+                Velocity::default(),
+                (GravityScale(gravity_scale), LocalGravity::default()),
+                (
+                    Monster {
+                        kind,
+                        patrol,
+                        patrol_bounds,
+                        ..default()
+                    },
+                    MonsterAi::new(position, kind, patrol),
+                    MonsterPath::default(),
+                ),
+                Health {
+                    invulnerability: 0.5,
+                    ..Health::new(kind.get_max_health())
+                },
+            ))
+            .id()
     }
 }
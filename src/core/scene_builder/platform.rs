@@ -0,0 +1,49 @@
+use crate::core::objects::collision_groups::WALL_CG;
+use crate::core::objects::platform::{MovingPlatform, PlatformPath, RotatingPlatform};
+use crate::core::scene_builder::wall::Wall;
+use crate::core::scene_builder::SceneBuilder;
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+use bevy_rapier2d::prelude::*;
+
+impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
+    pub fn spawn_moving_platform(&mut self, size: Vec2, path: PlatformPath) -> Entity {
+        let position = path.waypoints.first().copied().unwrap_or_default();
+        let entity = self.spawn_kinematic_platform(position, size);
+        self.commands
+            .entity(entity)
+            .insert(MovingPlatform::new(path));
+        entity
+    }
+
+    pub fn spawn_rotating_platform(
+        &mut self,
+        position: Vec2,
+        size: Vec2,
+        angular_speed: f32,
+    ) -> Entity {
+        let entity = self.spawn_kinematic_platform(position, size);
+        self.commands
+            .entity(entity)
+            .insert(RotatingPlatform { angular_speed });
+        entity
+    }
+
+    fn spawn_kinematic_platform(&mut self, position: Vec2, size: Vec2) -> Entity {
+        self.commands
+            .spawn((
+                Wall::default(),
+                Collider::cuboid(size.x * 0.5, size.y * 0.5),
+                RigidBody::KinematicVelocityBased,
+                Velocity::zero(),
+                MaterialMesh2dBundle {
+                    mesh: self.meshes.add(shape::Quad::new(size).into()).into(),
+                    material: self.materials.add(Color::rgb(0.15, 0.15, 0.2).into()),
+                    transform: Transform::from_xyz(position.x, position.y, Self::WALL_DEPTH),
+                    ..default()
+                },
+                WALL_CG,
+            ))
+            .id()
+    }
+}
@@ -1,11 +1,13 @@
-use crate::core::materials::player_material::PlayerMaterial;
-use crate::core::objects::player::Player;
+use crate::core::input::ActionState;
+use crate::core::objects::gravity_field::LocalGravity;
+use crate::core::objects::health::Health;
+use crate::core::objects::player::{spawn_player_sides, Player};
+use crate::core::objects::respawn::SpawnPoint;
 
-use crate::core::objects::shape::{PlayerShapeVisualBundle, MAX_SIDES};
+use crate::core::objects::shape::{PlayerShape, PlayerShapeVisualBundle, MAX_SIDES};
 use crate::core::objects::side_effect::SideEffect;
 use crate::core::scene_builder::SceneBuilder;
 use bevy::prelude::*;
-use std::f32::consts::PI;
 
 use crate::core::objects::collision_groups::PLAYER_CG;
 use bevy_rapier2d::prelude::*;
@@ -13,6 +15,11 @@ use bevy_rapier2d::prelude::*;
 #[derive(Bundle)]
 pub struct PlayerBundle {
     player: Player,
+    actions: ActionState,
+    gravity: LocalGravity,
+    gravity_scale: GravityScale,
+    health: Health,
+    spawn_point: SpawnPoint,
     rigid_body: RigidBody,
     velocity: Velocity,
     axes: LockedAxes,
@@ -30,55 +37,41 @@ pub struct PlayerBundle {
 }
 
 impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
-    pub fn spawn_player(&mut self, position: Vec2, id: u32) {
+    pub fn spawn_player(&mut self, position: Vec2, id: u32, shape: PlayerShape) {
         let mut player = Player {
             id,
+            shape,
             effects: [SideEffect::None; MAX_SIDES],
             side_entities: [None; MAX_SIDES],
-            small_collider: Collider::round_cuboid(0.4, 0.4, 0.075),
+            small_collider: shape.get_default_collider(),
             ..default()
         };
 
-        let player_material = PlayerMaterial {
-            color: Color::WHITE,
-            effect_index: [0; MAX_SIDES],
-            texture: Some(self.asset_server.load("images/square.png")),
-            emissive: None,
-            overlay: None,
-            player_effect_texture: self.asset_server.load("images/effect.png"),
-        };
+        let visual = self.psv.get_or_insert(
+            shape,
+            &mut self.meshes,
+            &mut self.player_materials,
+            &self.asset_server,
+        );
 
-        let parent = self.commands.spawn({}).id();
+        // Every player needs its own material to show its own side effects
+        let player_material = self
+            .player_materials
+            .get(&visual.material)
+            .cloned()
+            .unwrap_or_else(|| shape.get_default_player_material(&self.asset_server));
 
-        let directions = player.get_side_directions();
+        let parent = self.commands.spawn({}).id();
 
-        for i in 0..MAX_SIDES {
-            let child = self
-                .commands
-                .spawn((
-                    VisibilityBundle::default(),
-                    TransformBundle::from_transform(
-                        Transform::from_translation(directions[i].extend(0.0) * 0.475)
-                            .with_rotation(Quat::from_axis_angle(Vec3::Z, PI * 0.5 * i as f32)),
-                    ),
-                    Collider::round_cuboid(0.45, 0.01, 0.01),
-                    Friction {
-                        coefficient: 0.3,
-                        combine_rule: CoefficientCombineRule::Min,
-                    },
-                    Restitution {
-                        coefficient: 0.0,
-                        combine_rule: CoefficientCombineRule::Max,
-                    },
-                    PLAYER_CG,
-                ))
-                .id();
-            player.side_entities[i] = Some(child);
-            self.commands.entity(parent).add_child(child);
-        }
+        player.side_entities = spawn_player_sides(&mut self.commands, parent, shape);
 
         self.commands.entity(parent).insert(PlayerBundle {
             player,
+            actions: ActionState::default(),
+            gravity: LocalGravity::default(),
+            gravity_scale: GravityScale::default(),
+            health: Health::new(3.0),
+            spawn_point: SpawnPoint(position),
             rigid_body: RigidBody::Dynamic,
             velocity: Default::default(),
             axes: LockedAxes::empty(),
@@ -97,12 +90,8 @@ impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
             )),
             density: ColliderMassProperties::Density(1.0),
             visual: PlayerShapeVisualBundle {
-                mesh: self
-                    .meshes
-                    .add(shape::Quad::new(Vec2::new(2.0, 2.0)).into())
-                    .into(),
                 material: self.player_materials.add(player_material),
-                collider: Collider::round_cuboid(0.4, 0.4, 0.075),
+                ..visual
             },
         });
     }
@@ -6,6 +6,8 @@ use super::SceneBuilder;
 pub struct SceneBoundaries {
     pub rect: Option<Rect>,
     pub view_range: Option<f32>,
+    // Camera never zooms out further than this to fit all the players
+    pub max_view_range: Option<f32>,
 }
 
 impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
@@ -21,4 +23,8 @@ impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
     pub fn set_min_view_range(&mut self, range: f32) {
         self.boundaries.view_range = Some(range);
     }
+
+    pub fn set_max_view_range(&mut self, range: f32) {
+        self.boundaries.max_view_range = Some(range);
+    }
 }
@@ -0,0 +1,27 @@
+use crate::core::objects::spawner::{Encounter, Spawner};
+use crate::core::scene_builder::SceneBuilder;
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+
+impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
+    pub fn spawn_spawner(&mut self, position: Vec2, spawner: Spawner) -> Entity {
+        self.commands
+            .spawn((
+                spawner,
+                MaterialMesh2dBundle {
+                    mesh: self.meshes.add(shape::Circle::new(0.4).into()).into(),
+                    material: self
+                        .materials
+                        .add(ColorMaterial::from(Color::rgba(0.8, 0.1, 0.2, 0.5))),
+                    transform: Transform::from_xyz(position.x, position.y, Self::FIELD_DEPTH),
+                    ..default()
+                },
+            ))
+            .id()
+    }
+
+    /// Encounter has no body, it only watches its area.
+    pub fn spawn_encounter(&mut self, encounter: Encounter) -> Entity {
+        self.commands.spawn(encounter).id()
+    }
+}
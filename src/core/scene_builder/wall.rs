@@ -1,6 +1,9 @@
-use crate::core::objects::collision_groups::WALL_CG;
+use crate::core::objects::collision_groups::{DARK_CG, DARK_WALL_LIT_CG, ONE_WAY_CG, WALL_CG};
+use crate::core::objects::flashlight::Dark;
+use crate::core::objects::one_way_platform::OneWayPlatform;
 use crate::core::scene_builder::SceneBuilder;
 use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
 use bevy::sprite::MaterialMesh2dBundle;
 use bevy_rapier2d::prelude::*;
 
@@ -8,24 +11,264 @@ use bevy_rapier2d::prelude::*;
 pub struct Wall;
 
 impl<'w, 's, 'a> SceneBuilder<'w, 's, 'a> {
-    pub fn spawn_wall_from_to(&mut self, from: Vec2, to: Vec2) {
+    pub fn spawn_wall_from_to(&mut self, from: Vec2, to: Vec2) -> Entity {
         let size = from.max(to) - from.min(to);
         let translation = (from + to) * 0.5;
-        self.commands.spawn((
-            Wall::default(),
-            Collider::cuboid(size.x * 0.5, size.y * 0.5),
-            RigidBody::Fixed,
-            MaterialMesh2dBundle {
-                mesh: self.meshes.add(shape::Quad::new(size).into()).into(),
-                material: self.materials.add(Color::rgb(0.1, 0.1, 0.1).into()),
-                transform: Transform::from_translation(Vec3::new(
-                    translation.x,
-                    translation.y,
-                    Self::WALL_DEPTH,
-                )),
-                ..default()
-            },
-            WALL_CG,
+        self.commands
+            .spawn((
+                Wall::default(),
+                Collider::cuboid(size.x * 0.5, size.y * 0.5),
+                RigidBody::Fixed,
+                MaterialMesh2dBundle {
+                    mesh: self.meshes.add(shape::Quad::new(size).into()).into(),
+                    material: self.materials.add(Color::rgb(0.1, 0.1, 0.1).into()),
+                    transform: Transform::from_translation(Vec3::new(
+                        translation.x,
+                        translation.y,
+                        Self::WALL_DEPTH,
+                    )),
+                    ..default()
+                },
+                WALL_CG,
+            ))
+            .id()
+    }
+
+    /// Wall that exists only while some flashlight lights it.
+    pub fn spawn_dark_wall_from_to(&mut self, from: Vec2, to: Vec2) -> Entity {
+        let entity = self.spawn_wall_from_to(from, to);
+        self.commands.entity(entity).insert((
+            Dark::new(DARK_WALL_LIT_CG),
+            DARK_CG,
+            Visibility::Hidden,
+        ));
+        entity
+    }
+
+    /// Thin platform that can be passed from below and stood on from above.
+    pub fn spawn_one_way_platform(&mut self, from: Vec2, to: Vec2) -> Entity {
+        let entity = self.spawn_wall_from_to(from, to);
+        self.commands.entity(entity).insert((
+            OneWayPlatform,
+            ActiveHooks::MODIFY_SOLVER_CONTACTS,
+            ONE_WAY_CG,
         ));
+        entity
+    }
+
+    /// Box of the given size rotated by `angle` (counter-clockwise) around its center.
+    pub fn spawn_rotated_box(&mut self, center: Vec2, size: Vec2, angle: f32) -> Entity {
+        let entity = self.spawn_wall_from_to(center - size * 0.5, center + size * 0.5);
+        self.commands.entity(entity).insert(
+            Transform::from_xyz(center.x, center.y, Self::WALL_DEPTH)
+                .with_rotation(Quat::from_rotation_z(angle)),
+        );
+        entity
+    }
+
+    /// Filled polygon, points can go in any order along the border and may form a concave shape.
+    ///
+    /// Polygons without area (e.g. all points on one line) are skipped and give `None`.
+    pub fn spawn_polygon_wall(&mut self, points: &[Vec2]) -> Option<Entity> {
+        let points = get_counter_clockwise(points);
+        if points.len() < 3 || get_signed_area(&points) < MIN_POLYGON_AREA {
+            warn!("Skipped polygon wall without area: {:?}", points);
+            return None;
+        }
+
+        let n = points.len() as u32;
+        let edges: Vec<[u32; 2]> = (0..n).map(|i| [i, (i + 1) % n]).collect();
+
+        let collider = if is_convex(&points) {
+            Collider::convex_hull(&points)
+        } else {
+            None
+        }
+        .unwrap_or_else(|| Collider::convex_decomposition(&points, &edges));
+
+        let mesh = get_polygon_mesh(&points, &triangulate(&points));
+        Some(self.spawn_wall_with_mesh(collider, mesh))
+    }
+
+    /// Slope from one point to another, filled down to the lower one.
+    ///
+    /// Horizontal or vertical slopes have no area and give `None`, use a plain wall for them.
+    pub fn spawn_slope(&mut self, from: Vec2, to: Vec2) -> Option<Entity> {
+        let corner = if from.y < to.y {
+            Vec2::new(to.x, from.y)
+        } else {
+            Vec2::new(from.x, to.y)
+        };
+        self.spawn_polygon_wall(&[from, to, corner])
+    }
+
+    /// Chain of thin segments, e.g. for an uneven ground.
+    pub fn spawn_polyline_wall(&mut self, points: &[Vec2], thickness: f32) -> Entity {
+        let collider = Collider::polyline(points.to_vec(), None);
+
+        // Quad along every segment
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        for segment in points.windows(2) {
+            let normal = (segment[1] - segment[0]).perp().normalize_or_zero() * thickness * 0.5;
+            let i = vertices.len() as u32;
+            vertices.extend([
+                segment[0] - normal,
+                segment[1] - normal,
+                segment[1] + normal,
+                segment[0] + normal,
+            ]);
+            indices.extend([i, i + 1, i + 2, i, i + 2, i + 3]);
+        }
+
+        let mesh = get_polygon_mesh(&vertices, &indices);
+        self.spawn_wall_with_mesh(collider, mesh)
+    }
+
+    fn spawn_wall_with_mesh(&mut self, collider: Collider, mesh: Mesh) -> Entity {
+        self.commands
+            .spawn((
+                Wall::default(),
+                collider,
+                RigidBody::Fixed,
+                MaterialMesh2dBundle {
+                    mesh: self.meshes.add(mesh).into(),
+                    material: self.materials.add(Color::rgb(0.1, 0.1, 0.1).into()),
+                    transform: Transform::from_xyz(0.0, 0.0, Self::WALL_DEPTH),
+                    ..default()
+                },
+                WALL_CG,
+            ))
+            .id()
+    }
+}
+
+// Smaller polygons can not be turned into a collider
+const MIN_POLYGON_AREA: f32 = 1e-4;
+
+fn get_signed_area(points: &[Vec2]) -> f32 {
+    (0..points.len())
+        .map(|i| points[i].perp_dot(points[(i + 1) % points.len()]))
+        .sum::<f32>()
+        * 0.5
+}
+
+fn get_counter_clockwise(points: &[Vec2]) -> Vec<Vec2> {
+    let mut points = points.to_vec();
+    if get_signed_area(&points) < 0.0 {
+        points.reverse();
+    }
+    points
+}
+
+/// Expects counter-clockwise points.
+fn is_convex(points: &[Vec2]) -> bool {
+    let n = points.len();
+    (0..n).all(|i| {
+        let a = points[i];
+        let b = points[(i + 1) % n];
+        let c = points[(i + 2) % n];
+        (b - a).perp_dot(c - b) >= 0.0
+    })
+}
+
+/// Ear clipping triangulation of a simple counter-clockwise polygon.
+fn triangulate(points: &[Vec2]) -> Vec<u32> {
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut indices = vec![];
+
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (
+                points[remaining[(i + n - 1) % n]],
+                points[remaining[i]],
+                points[remaining[(i + 1) % n]],
+            );
+            if (b - a).perp_dot(c - b) <= 0.0 {
+                return false;
+            }
+            // No other point inside of the ear
+            remaining.iter().all(|&j| {
+                let p = points[j];
+                p == a
+                    || p == b
+                    || p == c
+                    || (b - a).perp_dot(p - a) < 0.0
+                    || (c - b).perp_dot(p - b) < 0.0
+                    || (a - c).perp_dot(p - c) < 0.0
+            })
+        });
+
+        // Degenerate polygon, take any vertex to finish
+        let i = ear.unwrap_or(0);
+        indices.extend([
+            remaining[(i + n - 1) % n] as u32,
+            remaining[i] as u32,
+            remaining[(i + 1) % n] as u32,
+        ]);
+        remaining.remove(i);
+    }
+
+    if remaining.len() == 3 {
+        indices.extend(remaining.iter().map(|&i| i as u32));
+    }
+
+    indices
+}
+
+fn get_polygon_mesh(points: &[Vec2], indices: &[u32]) -> Mesh {
+    let positions: Vec<[f32; 3]> = points.iter().map(|p| [p.x, p.y, 0.0]).collect();
+    let normals: Vec<[f32; 3]> = points.iter().map(|_| [0.0, 0.0, 1.0]).collect();
+    let uvs: Vec<[f32; 2]> = points.iter().map(|p| [p.x, -p.y]).collect();
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.set_indices(Some(Indices::U32(indices.to_vec())));
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::headless::{HeadlessApp, SECOND};
+    use crate::core::objects::shape::PlayerShape;
+    use bevy::prelude::*;
+    use bevy_rapier2d::prelude::*;
+
+    #[test]
+    fn player_settles_on_slope() {
+        let mut app = HeadlessApp::new();
+        app.build_scene(|builder| {
+            builder.spawn_slope(Vec2::new(-4.0, -2.0), Vec2::new(4.0, 0.0));
+            builder.spawn_player(Vec2::new(0.0, 0.5), 0, PlayerShape::Square);
+        });
+        app.run(3 * SECOND);
+
+        let entity = app.get_player_entity(0);
+        let player = app.get_player(0);
+        let transform = app.get_player_transform(0);
+        let velocity = app.app.world.get::<Velocity>(entity).unwrap();
+
+        // Player rests on the slope surface y = -1.0 + x / 4 near the point it fell to
+        let position = transform.translation.truncate();
+        assert!(player.landed_state.is_active);
+        assert!(velocity.linvel.length() < 0.1);
+        assert!(position.x.abs() < 2.0);
+        assert!(position.y > -1.0 + position.x * 0.25);
+    }
+
+    #[test]
+    fn degenerate_slope_is_skipped() {
+        let mut app = HeadlessApp::new();
+        app.build_scene(|builder| {
+            assert!(builder
+                .spawn_slope(Vec2::new(-2.0, 0.0), Vec2::new(2.0, 0.0))
+                .is_none());
+            assert!(builder
+                .spawn_polygon_wall(&[Vec2::ZERO, Vec2::ONE, Vec2::splat(2.0)])
+                .is_none());
+        });
     }
 }
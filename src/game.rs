@@ -1,25 +1,86 @@
-use crate::core::scene_builder::scene_boundaries::SceneBoundaries;
-use crate::core::scene_builder::SceneBuilder;
+use crate::core::deterministic::DeterministicSettings;
+use crate::core::level::CurrentLevel;
+use crate::core::replay::{Replay, ReplayPlayback, ReplayRecorder};
 use crate::core::CorePlugin;
 use crate::states::GameWorldState;
 use bevy::core_pipeline::bloom::BloomSettings;
 
 use bevy::prelude::*;
 
-use crate::core::materials::player_material::PlayerMaterial;
-use crate::core::objects::shape::PlayerShapeVisualBundleCache;
-use crate::core::objects::side_effect::SideEffect;
 use bevy::core_pipeline::tonemapping::Tonemapping;
 
-use crate::core::materials::bonus_material::BonusMaterial;
-use crate::core::materials::monster_material::MonsterMaterial;
 use std::env;
 
-pub struct SideEffectGamePlugin;
+/// Options given on the command line.
+#[derive(Clone, Debug, Default)]
+pub struct GameOptions {
+    // Turns on the deterministic mode with this seed
+    pub seed: Option<u64>,
+    // Records a replay and saves it here on exit
+    pub record: Option<String>,
+    // Plays the replay from this file instead of the live input
+    pub play: Option<String>,
+    // Plays the replay as fast as possible
+    pub fast: bool,
+}
+
+impl GameOptions {
+    /// Parses `--seed <n>`, `--record <path>`, `--play <path>` and `--fast`.
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        let mut options = GameOptions::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => match args.next().map(|seed| seed.parse()) {
+                    Some(Ok(seed)) => options.seed = Some(seed),
+                    _ => warn!("--seed needs a number"),
+                },
+                "--record" => options.record = args.next(),
+                "--play" => options.play = args.next(),
+                "--fast" => options.fast = true,
+                _ => warn!("Unknown argument {}", arg),
+            }
+        }
+
+        options
+    }
+}
+
+pub struct SideEffectGamePlugin {
+    pub options: GameOptions,
+}
 
 impl Plugin for SideEffectGamePlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<GameWorldState>();
+
+        if let Some(seed) = self.options.seed {
+            app.insert_resource(DeterministicSettings { seed, ..default() });
+        }
+
+        // Replays are only valid in the deterministic mode, so both options turn it on
+        if let Some(path) = &self.options.play {
+            match Replay::load(path) {
+                Ok(replay) => {
+                    app.insert_resource(replay.get_settings());
+                    app.insert_resource(ReplayPlayback::new(replay, self.options.fast));
+                }
+                Err(e) => error!("Failed to load replay {}: {}", path, e),
+            }
+        } else if let Some(path) = &self.options.record {
+            let settings = app
+                .world
+                .get_resource::<DeterministicSettings>()
+                .cloned()
+                .unwrap_or_default();
+            app.insert_resource(ReplayRecorder {
+                path: path.clone(),
+                replay: Replay::new(&settings),
+                step: 0,
+            });
+            app.insert_resource(settings);
+        }
+
         app.add_plugin(CorePlugin);
 
         if env::var("LOCAL_BUILD") == Ok("2".to_string()) {
@@ -45,47 +106,6 @@ fn setup_camera(mut commands: Commands) {
         .insert(BloomSettings::default());
 }
 
-fn setup_dev(
-    commands: Commands,
-    asset_server: Res<AssetServer>,
-    meshes: ResMut<Assets<Mesh>>,
-    materials: ResMut<Assets<ColorMaterial>>,
-    player_materials: ResMut<Assets<PlayerMaterial>>,
-    bonus_materials: ResMut<Assets<BonusMaterial>>,
-    monster_materials: ResMut<Assets<MonsterMaterial>>,
-    boundaries: ResMut<SceneBoundaries>,
-    psv: ResMut<PlayerShapeVisualBundleCache>,
-    mut next_game_state: ResMut<NextState<GameWorldState>>,
-) {
-    let mut scene_builder = SceneBuilder::new(
-        commands,
-        asset_server,
-        meshes,
-        materials,
-        player_materials,
-        bonus_materials,
-        monster_materials,
-        boundaries,
-        psv,
-    );
-
-    scene_builder.spawn_player(Vec2::new(0.0, 1.0), 0);
-    scene_builder.spawn_effect_bonus(Vec2::new(2.0, -1.75), SideEffect::Sticky);
-    scene_builder.spawn_effect_bonus(Vec2::new(3.0, -1.75), SideEffect::Shield);
-    scene_builder.spawn_effect_bonus(Vec2::new(4.0, -1.75), SideEffect::Slippery);
-    scene_builder.spawn_effect_bonus(Vec2::new(5.0, -1.75), SideEffect::Shield);
-    /*scene_builder.spawn_effect_bonus(Vec2::new(2.0, -1.2), SideEffect::Thorns);
-    scene_builder.spawn_effect_bonus(Vec2::new(3.0, -1.2), SideEffect::Flashlight);
-    scene_builder.spawn_effect_bonus(Vec2::new(4.0, -1.2), SideEffect::Laser);
-    scene_builder.spawn_effect_bonus(Vec2::new(5.0, -1.2), SideEffect::Spring);*/
-
-    scene_builder.spawn_monster(Vec2::new(2.0, 1.2));
-
-    scene_builder.spawn_wall_from_to(Vec2::new(-5.0, -2.0), Vec2::new(5.0, -3.0));
-    //scene_builder.spawn_wall_from_to(Vec2::new(2.0, 0.0), Vec2::new(5.0, -0.5));
-    scene_builder.spawn_wall_from_to(Vec2::new(-6.0, -2.0), Vec2::new(-5.0, 2.0));
-    scene_builder.spawn_wall_from_to(Vec2::new(5.0, -2.0), Vec2::new(6.0, 2.0));
-    scene_builder.spawn_wall_from_to(Vec2::new(2.0, -1.0), Vec2::new(3.0, -2.0));
-
-    next_game_state.set(GameWorldState::GameWorld);
+fn setup_dev(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CurrentLevel::new(asset_server.load("levels/dev.level.ron")));
 }
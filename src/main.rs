@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use crate::game::SideEffectGamePlugin;
+use crate::game::{GameOptions, SideEffectGamePlugin};
 use bevy::prelude::*;
 
 mod core;
@@ -19,7 +19,9 @@ fn main() {
                 ..default()
             }),
     );
-    app.add_plugin(SideEffectGamePlugin);
+    app.add_plugin(SideEffectGamePlugin {
+        options: GameOptions::from_args(std::env::args().skip(1)),
+    });
 
     app.run();
 }